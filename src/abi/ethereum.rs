@@ -25,8 +25,9 @@ pub struct ABI {
     #[serde(rename = "type")]
     pub ty: String,
     pub inputs: Vec<ABIParam>,
-    // outputs should be skipped if ty is constructor
-    pub outputs: Vec<ABIParam>,
+    // outputs are skipped if ty is constructor
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<ABIParam>>,
     #[serde(rename = "stateMutability")]
     pub mutability: String,
     #[serde(skip_serializing_if = "is_false")]
@@ -114,32 +115,39 @@ pub fn gen_abi(contract_no: usize, ns: &Namespace) -> Vec<ABI> {
                 .iter()
                 .map(|p| parameter_to_abi(p, ns))
                 .collect(),
-            outputs: func
-                .returns
-                .iter()
-                .map(|p| parameter_to_abi(p, ns))
-                .collect(),
+            outputs: if func.ty == pt::FunctionTy::Constructor {
+                None
+            } else {
+                Some(
+                    func.returns
+                        .iter()
+                        .map(|p| parameter_to_abi(p, ns))
+                        .collect(),
+                )
+            },
             anonymous: false,
         })
         .chain(
-            ns.contracts[contract_no]
-                .sends_events
+            // the events declared in the contract, and any other events it emits, so
+            // that off-chain decoders can parse every log
+            ns.events
                 .iter()
-                .map(|event_no| {
-                    let event = &ns.events[*event_no];
-
-                    ABI {
-                        name: event.name.to_owned(),
-                        mutability: String::new(),
-                        inputs: event
-                            .fields
-                            .iter()
-                            .map(|p| parameter_to_abi(p, ns))
-                            .collect(),
-                        outputs: Vec::new(),
-                        ty: "event".to_owned(),
-                        anonymous: event.anonymous,
-                    }
+                .enumerate()
+                .filter(|(event_no, event)| {
+                    event.contract == Some(contract_no)
+                        || ns.contracts[contract_no].sends_events.contains(event_no)
+                })
+                .map(|(_, event)| ABI {
+                    name: event.name.to_owned(),
+                    mutability: String::new(),
+                    inputs: event
+                        .fields
+                        .iter()
+                        .map(|p| parameter_to_abi(p, ns))
+                        .collect(),
+                    outputs: Some(Vec::new()),
+                    ty: "event".to_owned(),
+                    anonymous: event.anonymous,
                 }),
         )
         .collect()
@@ -123,6 +123,59 @@ fn main() {
                 .long("math-overflow")
                 .display_order(5),
         )
+        .arg(
+            Arg::with_name("METERGAS")
+                .help("Call meter_gas at the start of every basic block (Lachain only)")
+                .long("meter-gas")
+                .display_order(6),
+        )
+        .arg(
+            Arg::with_name("STRICTABIDECODE")
+                .help("Revert on non-canonical abi encoded arguments (Lachain only)")
+                .long("strict-abi-decode")
+                .display_order(7),
+        )
+        .arg(
+            Arg::with_name("MAXRETURNDATA")
+                .help("Maximum number of bytes of return data copied after a call (Lachain only)")
+                .long("max-return-data")
+                .takes_value(true)
+                .display_order(8),
+        )
+        .arg(
+            Arg::with_name("MAXREVERTDATA")
+                .help("Maximum number of bytes of revert data copied after a failed call (Lachain only)")
+                .long("max-revert-data")
+                .takes_value(true)
+                .display_order(9),
+        )
+        .arg(
+            Arg::with_name("HASHHOSTBYTEORDER")
+                .help("Do not reverse the bytes returned by the hash host functions (Lachain only)")
+                .long("hash-host-byte-order")
+                .display_order(10),
+        )
+        .arg(
+            Arg::with_name("CONTRACTOPT")
+                .help("Set llvm optimizer level for a contract, as contract=level")
+                .long("contract-opt")
+                .takes_value(true)
+                .multiple(true)
+                .display_order(11),
+        )
+        .arg(
+            Arg::with_name("CONTRACTSIZELIMIT")
+                .help("Maximum size of contract code in bytes")
+                .long("contract-size-limit")
+                .takes_value(true)
+                .display_order(12),
+        )
+        .arg(
+            Arg::with_name("CONTRACTSIZELIMITERROR")
+                .help("Exceeding the contract size limit is an error rather than a warning")
+                .long("contract-size-limit-error")
+                .display_order(13),
+        )
         .arg(
             Arg::with_name("LANGUAGESERVER")
                 .help("Start language server on stdin/stdout")
@@ -219,13 +272,28 @@ fn main() {
             doc::generate_docs(matches.value_of("OUTPUT").unwrap_or("."), &files, verbose);
         }
     } else {
-        let opt_level = match matches.value_of("OPT").unwrap() {
-            "none" => inkwell::OptimizationLevel::None,
-            "less" => inkwell::OptimizationLevel::Less,
-            "default" => inkwell::OptimizationLevel::Default,
-            "aggressive" => inkwell::OptimizationLevel::Aggressive,
-            _ => unreachable!(),
-        };
+        let opt_level = parse_opt_level(matches.value_of("OPT").unwrap()).unwrap();
+
+        let mut contract_opt_level = HashMap::new();
+
+        if let Some(values) = matches.values_of("CONTRACTOPT") {
+            for v in values {
+                let mut parts = v.splitn(2, '=');
+
+                match (parts.next(), parts.next().and_then(parse_opt_level)) {
+                    (Some(name), Some(level)) => {
+                        contract_opt_level.insert(name.to_owned(), level);
+                    }
+                    _ => {
+                        eprintln!(
+                            "error: contract optimizer level ‘{}’ should be contract=level, where level is none, less, default or aggressive",
+                            v
+                        );
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
 
         let opt = Options {
             dead_storage: !matches.is_present("DEADSTORAGE"),
@@ -234,6 +302,14 @@ fn main() {
             vector_to_slice: !matches.is_present("VECTORTOSLICE"),
             math_overflow_check,
             opt_level,
+            meter_gas: matches.is_present("METERGAS"),
+            strict_abi_decode: matches.is_present("STRICTABIDECODE"),
+            max_return_data: byte_count(&matches, "MAXRETURNDATA"),
+            max_revert_data: byte_count(&matches, "MAXREVERTDATA"),
+            hash_host_byte_order: matches.is_present("HASHHOSTBYTEORDER"),
+            contract_opt_level,
+            contract_size_limit: byte_count(&matches, "CONTRACTSIZELIMIT"),
+            contract_size_limit_error: matches.is_present("CONTRACTSIZELIMITERROR"),
         };
 
         let mut namespaces = Vec::new();
@@ -351,6 +427,28 @@ fn main() {
     }
 }
 
+/// Parse an llvm optimizer level
+fn parse_opt_level(level: &str) -> Option<inkwell::OptimizationLevel> {
+    match level {
+        "none" => Some(inkwell::OptimizationLevel::None),
+        "less" => Some(inkwell::OptimizationLevel::Less),
+        "default" => Some(inkwell::OptimizationLevel::Default),
+        "aggressive" => Some(inkwell::OptimizationLevel::Aggressive),
+        _ => None,
+    }
+}
+
+/// Parse an option which takes a number of bytes
+fn byte_count(matches: &ArgMatches, name: &str) -> Option<usize> {
+    matches.value_of(name).map(|v| match v.parse() {
+        Ok(n) => n,
+        Err(_) => {
+            eprintln!("error: ‘{}’ is not a valid number of bytes", v);
+            std::process::exit(1);
+        }
+    })
+}
+
 fn output_file(matches: &ArgMatches, stem: &str, ext: &str) -> PathBuf {
     Path::new(matches.value_of("OUTPUT").unwrap_or(".")).join(format!("{}.{}", stem, ext))
 }
@@ -422,13 +520,32 @@ fn process_filename(
 
         let context = inkwell::context::Context::create();
 
-        let binary = resolved_contract.emit(
-            &ns,
-            &context,
-            filename,
-            opt.opt_level,
-            opt.math_overflow_check,
-        );
+        let binary = resolved_contract.emit(&ns, &context, filename, opt);
+
+        if let Some(table) = binary.gas_meter_table(&ns) {
+            let table_filename = output_file(matches, &binary.name, "gas.json");
+
+            if verbose {
+                eprintln!(
+                    "info: Saving gas meter table {} for contract {}",
+                    table_filename.display(),
+                    binary.name
+                );
+            }
+
+            let mut file = match File::create(&table_filename) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!(
+                        "error: cannot create file ‘{}’: {}",
+                        table_filename.display(),
+                        err,
+                    );
+                    std::process::exit(1);
+                }
+            };
+            file.write_all(table.as_bytes()).unwrap();
+        }
 
         if save_intermediates(&binary, matches) {
             continue;
@@ -531,6 +648,36 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
                 }
 
                 runtime.dump_llvm(&llvm_filename).unwrap();
+
+                binary.optimize();
+
+                let llvm_filename =
+                    output_file(matches, &format!("{}_deploy", binary.name), "opt.ll");
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving optimized deployer LLVM {} for contract {}",
+                        llvm_filename.display(),
+                        binary.name
+                    );
+                }
+
+                binary.dump_llvm(&llvm_filename).unwrap();
+
+                runtime.optimize();
+
+                let llvm_filename =
+                    output_file(matches, &format!("{}_runtime", binary.name), "opt.ll");
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving optimized runtime LLVM {} for contract {}",
+                        llvm_filename.display(),
+                        binary.name
+                    );
+                }
+
+                runtime.dump_llvm(&llvm_filename).unwrap();
             } else {
                 let llvm_filename = output_file(matches, &binary.name, "ll");
 
@@ -543,6 +690,20 @@ fn save_intermediates(binary: &solang::emit::Binary, matches: &ArgMatches) -> bo
                 }
 
                 binary.dump_llvm(&llvm_filename).unwrap();
+
+                binary.optimize();
+
+                let llvm_filename = output_file(matches, &binary.name, "opt.ll");
+
+                if verbose {
+                    eprintln!(
+                        "info: Saving optimized LLVM IR {} for contract {}",
+                        llvm_filename.display(),
+                        binary.name
+                    );
+                }
+
+                binary.dump_llvm(&llvm_filename).unwrap();
             }
 
             true
@@ -49,6 +49,8 @@ pub enum Instr {
     Store { dest: Expression, pos: usize },
     /// Abort execution
     AssertFailure { expr: Option<Expression> },
+    /// Abort execution with a Panic(uint256) code, like out of bounds access
+    Panic { code: u8 },
     /// Print to log message
     Print { expr: Expression },
     /// Load storage (this is an instruction rather than an expression
@@ -66,6 +68,11 @@ pub enum Instr {
         value: Expression,
         storage: Expression,
     },
+    /// Append to bytes or string in storage, without loading it first
+    AppendStorageBytes {
+        value: Expression,
+        storage: Expression,
+    },
     /// In storage slot, set the value at the offset
     SetStorageBytes {
         value: Expression,
@@ -173,6 +180,7 @@ impl Instr {
             }
 
             Instr::SetStorage { value, storage, .. }
+            | Instr::AppendStorageBytes { value, storage }
             | Instr::PushStorage { value, storage, .. } => {
                 value.recurse(cx, f);
                 storage.recurse(cx, f);
@@ -250,6 +258,7 @@ impl Instr {
             }
 
             Instr::AssertFailure { expr: None }
+            | Instr::Panic { .. }
             | Instr::Unreachable
             | Instr::Nop
             | Instr::Branch { .. }
@@ -823,6 +832,11 @@ impl ControlFlowGraph {
                 ty.to_string(ns),
                 self.expr_to_string(contract, ns, value),
             ),
+            Instr::AppendStorageBytes { value, storage } => format!(
+                "append storage slot({}) = {}",
+                self.expr_to_string(contract, ns, storage),
+                self.expr_to_string(contract, ns, value),
+            ),
             Instr::SetStorageBytes {
                 value,
                 storage,
@@ -872,6 +886,7 @@ impl ControlFlowGraph {
                 ty.to_string(ns),
             ),
             Instr::AssertFailure { expr: None } => "assert-failure".to_string(),
+            Instr::Panic { code } => format!("panic:{:#04x}", code),
             Instr::AssertFailure { expr: Some(expr) } => {
                 format!("assert-failure:{}", self.expr_to_string(contract, ns, expr))
             }
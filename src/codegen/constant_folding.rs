@@ -142,6 +142,13 @@ pub fn constant_folding(cfg: &mut ControlFlowGraph, ns: &mut Namespace) {
                         value,
                     };
                 }
+                Instr::AppendStorageBytes { value, storage } => {
+                    let (storage, _) = expression(storage, Some(&vars), &cur, cfg, ns);
+                    let (value, _) = expression(value, Some(&vars), &cur, cfg, ns);
+
+                    cfg.blocks[block_no].instr[instr_no] =
+                        Instr::AppendStorageBytes { value, storage };
+                }
                 Instr::PopStorage { res, ty, storage } => {
                     let (storage, _) = expression(storage, Some(&vars), &cur, cfg, ns);
 
@@ -236,6 +236,7 @@ fn instr_transfers(block_no: usize, block: &BasicBlock) -> Vec<Vec<Transfer>> {
                 ]
             }
             Instr::SetStorageBytes { storage, .. }
+            | Instr::AppendStorageBytes { storage, .. }
             | Instr::ClearStorage { storage, .. }
             | Instr::SetStorage { storage, .. } => {
                 vec![Transfer::Store {
@@ -525,7 +526,9 @@ pub fn dead_storage(cfg: &mut ControlFlowGraph, _ns: &mut Namespace) {
                         }
                     }
                 }
-                Instr::PushStorage { storage, .. } | Instr::PopStorage { storage, .. } => {
+                Instr::PushStorage { storage, .. }
+                | Instr::PopStorage { storage, .. }
+                | Instr::AppendStorageBytes { storage, .. } => {
                     for (def, expr) in &vars.stores {
                         let def_vars = get_vars_at(def, &block_vars);
 
@@ -537,10 +537,19 @@ pub fn expression(
             string_location(left, cfg, contract_no, func, ns, vartab),
             string_location(right, cfg, contract_no, func, ns, vartab),
         ),
-        Expression::DynamicArrayLength(loc, expr) => Expression::DynamicArrayLength(
-            *loc,
-            Box::new(expression(expr, cfg, contract_no, func, ns, vartab)),
-        ),
+        Expression::DynamicArrayLength(loc, expr) => match expr.as_ref() {
+            // the length of the code at an address does not need a copy of the code
+            Expression::Builtin(_, _, Builtin::ExternalCode, args) => Expression::Builtin(
+                *loc,
+                vec![Type::Uint(32)],
+                Builtin::ExternalCodeSize,
+                vec![expression(&args[0], cfg, contract_no, func, ns, vartab)],
+            ),
+            _ => Expression::DynamicArrayLength(
+                *loc,
+                Box::new(expression(expr, cfg, contract_no, func, ns, vartab)),
+            ),
+        },
         Expression::DynamicArrayPush(loc, array, ty, value) => {
             let elem_ty = match ty {
                 Type::Array(..) => match ty.array_elem() {
@@ -976,7 +985,8 @@ pub fn expression(
 
             Expression::Variable(*loc, Type::DynamicBytes, res)
         }
-        Expression::Builtin(loc, _, Builtin::AbiEncodeWithSelector, args) => {
+        Expression::Builtin(loc, _, Builtin::AbiEncodeWithSelector, args)
+        | Expression::Builtin(loc, _, Builtin::AbiEncodeCall, args) => {
             let mut tys: Vec<Type> = args.iter().skip(1).map(|a| a.ty()).collect();
             // first argument is selector
             let mut args_iter = args.iter();
@@ -1735,7 +1745,8 @@ fn array_subscript(
     );
 
     cfg.set_basic_block(out_of_bounds);
-    cfg.add(vartab, Instr::AssertFailure { expr: None });
+    // Panic(0x32): array index out of bounds
+    cfg.add(vartab, Instr::Panic { code: 0x32 });
 
     cfg.set_basic_block(in_bounds);
 
@@ -3,6 +3,7 @@ mod constant_folding;
 mod dead_storage;
 mod expression;
 mod external_functions;
+mod reachability;
 mod reaching_definitions;
 mod statements;
 mod storage;
@@ -13,7 +14,7 @@ mod vector_to_slice;
 
 use self::cfg::{optimize_and_check_cfg, ControlFlowGraph, Instr, Vartable};
 use self::expression::expression;
-use crate::emit::Generate;
+use crate::emit;
 use crate::sema::ast::{Layout, Namespace};
 use crate::sema::contracts::visit_bases;
 use crate::sema::diagnostics::any_errors;
@@ -21,10 +22,15 @@ use crate::Target;
 
 use num_bigint::BigInt;
 use num_traits::Zero;
+use std::collections::HashMap;
 
 // The sizeof(struct account_data_header)
 pub const SOLANA_FIRST_OFFSET: u64 = 16;
 
+// Maximum contract code size as specified in EIP-170
+pub const EIP170_CONTRACT_SIZE_LIMIT: usize = 24576;
+
+#[derive(Clone)]
 pub struct Options {
     pub dead_storage: bool,
     pub constant_folding: bool,
@@ -32,6 +38,29 @@ pub struct Options {
     pub vector_to_slice: bool,
     pub math_overflow_check: bool,
     pub opt_level: inkwell::OptimizationLevel,
+    /// Call the `meter_gas` host function at the start of every basic block, and write a table
+    /// mapping the block ids to source locations next to the source file (Lachain only)
+    pub meter_gas: bool,
+    /// Revert when a bool or uintN argument is not canonically ABI encoded, i.e. the 32 byte
+    /// word has bits set beyond the type (Lachain only)
+    pub strict_abi_decode: bool,
+    /// Maximum number of bytes of return data copied after an external call; anything beyond
+    /// this is dropped. If not set, all of the return data is copied (Lachain only)
+    pub max_return_data: Option<usize>,
+    /// Maximum number of bytes of return data copied after an external call or contract
+    /// creation failed, i.e. the revert data. If not set, only `max_return_data` applies
+    /// (Lachain only)
+    pub max_revert_data: Option<usize>,
+    /// Keep the bytes returned by the hash host functions in the order the host wrote them,
+    /// rather than reversing them for `bytesN` (Lachain only)
+    pub hash_host_byte_order: bool,
+    /// Optimization level for specific contracts, by contract name. Contracts not listed
+    /// here are built with `opt_level`
+    pub contract_opt_level: HashMap<String, inkwell::OptimizationLevel>,
+    /// Maximum size of the code of a contract in bytes. If not set, the default for the target is used
+    pub contract_size_limit: Option<usize>,
+    /// Exceeding the contract size limit is an error rather than a warning
+    pub contract_size_limit_error: bool,
 }
 
 impl Default for Options {
@@ -43,10 +72,37 @@ impl Default for Options {
             vector_to_slice: true,
             math_overflow_check: false,
             opt_level: inkwell::OptimizationLevel::Default,
+            meter_gas: false,
+            strict_abi_decode: false,
+            max_return_data: None,
+            max_revert_data: None,
+            hash_host_byte_order: false,
+            contract_opt_level: HashMap::new(),
+            contract_size_limit: None,
+            contract_size_limit_error: false,
         }
     }
 }
 
+impl Options {
+    /// The optimization level the given contract should be built with
+    pub fn opt_level_for(&self, contract_name: &str) -> inkwell::OptimizationLevel {
+        self.contract_opt_level
+            .get(contract_name)
+            .copied()
+            .unwrap_or(self.opt_level)
+    }
+
+    /// The maximum code size for contracts on the given target, if any. On ewasm the code is
+    /// the deployer, which embeds the runtime code, so EIP-170 does not apply to it as a whole.
+    pub fn contract_size_limit_for(&self, target: Target) -> Option<usize> {
+        self.contract_size_limit.or(match target {
+            Target::Lachain => Some(EIP170_CONTRACT_SIZE_LIMIT),
+            _ => None,
+        })
+    }
+}
+
 /// The contracts are fully resolved but they do not have any a CFG which is needed for
 /// the llvm code emitter. This will also do addition code checks.
 pub fn codegen(ns: &mut Namespace, opt: &Options) {
@@ -85,30 +141,11 @@ pub fn codegen(ns: &mut Namespace, opt: &Options) {
                 return;
             }
 
-            // Solana creates a single bundle
-            if ns.target != Target::Solana && ns.target != Target::Generic {
-                let context = inkwell::context::Context::create();
-
-                let filename = ns.files[0].path.to_string_lossy();
-
-                let binary = ns.contracts[contract_no].emit(
-                    ns,
-                    &context,
-                    &filename,
-                    opt.opt_level,
-                    opt.math_overflow_check,
-                );
-
-                let code = binary.code(Generate::Linked).expect("llvm build");
-
-                drop(binary);
-
-                ns.contracts[contract_no].code = code;
-            }
-
             contracts_done[contract_no] = true;
         }
     }
+
+    emit::build_all(ns, opt);
 }
 
 fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
@@ -164,6 +201,10 @@ fn contract(contract_no: usize, ns: &mut Namespace, opt: &Options) {
         }
 
         ns.contracts[contract_no].cfg = all_cfg;
+
+        if ns.target == Target::Lachain {
+            reachability::unreachable_functions(contract_no, ns);
+        }
     }
 }
 
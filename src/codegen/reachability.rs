@@ -0,0 +1,94 @@
+use crate::codegen::cfg::{ControlFlowGraph, Instr, InternalCallTy};
+use crate::parser::pt;
+use crate::sema::ast::{Diagnostic, Expression, Namespace};
+use std::collections::HashSet;
+
+/// Warn about internal functions of a contract which cannot be reached from any of its entry
+/// points. The entry points are the public functions, constructors, fallback and receive, and
+/// the compiler generated cfgs such as the storage initializer.
+pub fn unreachable_functions(contract_no: usize, ns: &mut Namespace) {
+    let contract = &ns.contracts[contract_no];
+
+    let mut reachable = HashSet::new();
+    let mut worklist: Vec<usize> = contract
+        .cfg
+        .iter()
+        .enumerate()
+        .filter(|(_, cfg)| {
+            !cfg.is_placeholder()
+                && (cfg.public || cfg.function_no.is_none() || cfg.ty != pt::FunctionTy::Function)
+        })
+        .map(|(cfg_no, _)| cfg_no)
+        .collect();
+
+    while let Some(cfg_no) = worklist.pop() {
+        if !reachable.insert(cfg_no) {
+            continue;
+        }
+
+        for callee in callees(&contract.cfg[cfg_no]) {
+            if !reachable.contains(&callee) {
+                worklist.push(callee);
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+
+    for (function_no, cfg_no) in &contract.all_functions {
+        let func = &ns.functions[*function_no];
+
+        // functions inherited from a base contract are checked when the base contract is compiled
+        if func.contract_no != Some(contract_no)
+            || func.ty != pt::FunctionTy::Function
+            || func.is_public()
+            || reachable.contains(cfg_no)
+        {
+            continue;
+        }
+
+        diagnostics.push(Diagnostic::warning(
+            func.loc,
+            format!("function ‘{}’ is never called", func.name),
+        ));
+    }
+
+    ns.diagnostics.extend(diagnostics);
+}
+
+/// Collect the cfgs which are called directly, or whose address is taken as a function pointer
+fn callees(cfg: &ControlFlowGraph) -> HashSet<usize> {
+    let mut callees = HashSet::new();
+
+    for block in &cfg.blocks {
+        for instr in &block.instr {
+            if let Instr::Call {
+                call: InternalCallTy::Static(cfg_no),
+                ..
+            } = instr
+            {
+                callees.insert(*cfg_no);
+            }
+
+            if let Instr::Call {
+                call: InternalCallTy::Dynamic(expr),
+                ..
+            } = instr
+            {
+                expr.recurse(&mut callees, function_pointers);
+            }
+
+            instr.recurse_expressions(&mut callees, function_pointers);
+        }
+    }
+
+    callees
+}
+
+fn function_pointers(expr: &Expression, callees: &mut HashSet<usize>) -> bool {
+    if let Expression::InternalFunctionCfg(cfg_no) = expr {
+        callees.insert(*cfg_no);
+    }
+
+    true
+}
@@ -8,9 +8,11 @@ use crate::codegen::unused_variable::{
 };
 use crate::parser::pt;
 use crate::sema::ast::{
-    Builtin, CallTy, DestructureField, Expression, Function, Namespace, Parameter, Statement, Type,
+    Builtin, CallTy, DestructureField, Expression, Function, Namespace, Parameter, Statement,
+    StringLocation, Type,
 };
 use crate::sema::expression::cast;
+use crate::Target;
 use num_traits::Zero;
 
 /// Resolve a statement, which might be a block of statements or an entire body of a function
@@ -112,6 +114,21 @@ pub fn statement(
                 }
             }
 
+            // appending to bytes or string in storage does not need to load it into memory
+            if ns.target == Target::Lachain {
+                if let Some((storage, value)) = storage_append(expr) {
+                    let storage = expression(storage, cfg, contract_no, Some(func), ns, vartab);
+                    let value = expression(value, cfg, contract_no, Some(func), ns, vartab);
+
+                    cfg.add(vartab, Instr::AppendStorageBytes { value, storage });
+
+                    if !reachable {
+                        cfg.add(vartab, Instr::Unreachable);
+                    }
+                    return;
+                }
+            }
+
             let _ = expression(expr, cfg, contract_no, Some(func), ns, vartab);
 
             if !reachable {
@@ -853,6 +870,28 @@ fn try_catch(
     let catch_block = cfg.new_basic_block("catch".to_string());
     let finally_block = cfg.new_basic_block("finally".to_string());
 
+    // On Lachain, a call to an account without code is caught too. The catch clauses then see
+    // empty revert data rather than the return data of an earlier call, so they read the
+    // revert data from a variable which is set on both paths.
+    let (failed_block, catch_data) = if ns.target == Target::Lachain {
+        let reason = vartab.temp(
+            &pt::Identifier {
+                loc: fcall.loc(),
+                name: "reason".to_owned(),
+            },
+            &Type::DynamicBytes,
+        );
+
+        (
+            cfg.new_basic_block("call_failed".to_string()),
+            Expression::Variable(fcall.loc(), Type::DynamicBytes, reason),
+        )
+    } else {
+        (catch_block, Expression::ReturnData(pt::Loc(0, 0, 0)))
+    };
+
+    let mut no_code_block = None;
+
     match &fcall {
         Expression::ExternalFunctionCall {
             loc,
@@ -902,6 +941,37 @@ fn try_catch(
                     args,
                 };
 
+                if ns.target == Target::Lachain {
+                    let call_block = cfg.new_basic_block("call".to_string());
+                    let block = cfg.new_basic_block("no_code".to_string());
+
+                    cfg.add(
+                        vartab,
+                        Instr::BranchCond {
+                            cond: Expression::Equal(
+                                *loc,
+                                Box::new(Expression::Builtin(
+                                    *loc,
+                                    vec![Type::Uint(32)],
+                                    Builtin::ExternalCodeSize,
+                                    vec![address.clone()],
+                                )),
+                                Box::new(Expression::NumberLiteral(
+                                    *loc,
+                                    Type::Uint(32),
+                                    BigInt::zero(),
+                                )),
+                            ),
+                            true_block: block,
+                            false_block: call_block,
+                        },
+                    );
+
+                    cfg.set_basic_block(call_block);
+
+                    no_code_block = Some(block);
+                }
+
                 cfg.add(
                     vartab,
                     Instr::ExternalCall {
@@ -919,7 +989,7 @@ fn try_catch(
                     Instr::BranchCond {
                         cond: Expression::Variable(fcall.loc(), Type::Bool, success),
                         true_block: success_block,
-                        false_block: catch_block,
+                        false_block: failed_block,
                     },
                 );
 
@@ -1015,7 +1085,7 @@ fn try_catch(
                 Instr::BranchCond {
                     cond: Expression::Variable(fcall.loc(), Type::Bool, success),
                     true_block: success_block,
-                    false_block: catch_block,
+                    false_block: failed_block,
                 },
             );
 
@@ -1024,6 +1094,49 @@ fn try_catch(
         _ => unreachable!(),
     }
 
+    if let Expression::Variable(_, _, reason) = catch_data {
+        cfg.set_basic_block(failed_block);
+
+        cfg.add(
+            vartab,
+            Instr::Set {
+                loc: pt::Loc(0, 0, 0),
+                res: reason,
+                expr: Expression::ReturnData(pt::Loc(0, 0, 0)),
+            },
+        );
+
+        cfg.add(vartab, Instr::Branch { block: catch_block });
+
+        if let Some(no_code_block) = no_code_block {
+            cfg.set_basic_block(no_code_block);
+
+            cfg.add(
+                vartab,
+                Instr::Set {
+                    loc: pt::Loc(0, 0, 0),
+                    res: reason,
+                    expr: Expression::AllocDynamicArray(
+                        pt::Loc(0, 0, 0),
+                        Type::DynamicBytes,
+                        Box::new(Expression::NumberLiteral(
+                            pt::Loc(0, 0, 0),
+                            Type::Uint(32),
+                            BigInt::zero(),
+                        )),
+                        None,
+                    ),
+                },
+            );
+
+            cfg.add(vartab, Instr::Branch { block: catch_block });
+        }
+
+        cfg.set_phis(catch_block, vec![reason].into_iter().collect());
+
+        cfg.set_basic_block(success_block);
+    }
+
     vartab.new_dirty_tracker(ns.next_id);
 
     let mut finally_reachable = true;
@@ -1070,7 +1183,7 @@ fn try_catch(
                 exception_block: Some(no_reason_block),
                 res: vec![error_var],
                 tys: vec![error_param.clone()],
-                data: Expression::ReturnData(pt::Loc(0, 0, 0)),
+                data: catch_data.clone(),
             },
         );
 
@@ -1109,7 +1222,7 @@ fn try_catch(
             Instr::Set {
                 loc: pt::Loc(0, 0, 0),
                 res: *pos,
-                expr: Expression::ReturnData(pt::Loc(0, 0, 0)),
+                expr: catch_data,
             },
         );
     }
@@ -1287,3 +1400,88 @@ pub fn process_side_effects_expressions(
         _ => true,
     }
 }
+
+/// Match `s = bytes.concat(s, value);` or `s = s + value;` where `s` is a bytes or string
+/// storage variable, and return the storage variable and the value to append. The value is
+/// evaluated before the append, so it must not refer to `s` or have any side effects;
+/// otherwise it could see or change `s` when the original expression would not.
+fn storage_append(expr: &Expression) -> Option<(&Expression, &Expression)> {
+    if let Expression::Assign(_, _, left, right) = expr {
+        if let Expression::StringConcat(
+            _,
+            _,
+            StringLocation::RunTime(current),
+            StringLocation::RunTime(value),
+        ) = right.as_ref()
+        {
+            if let (
+                Expression::StorageVariable(_, _, var_contract_no, var_no),
+                Expression::StorageLoad(_, _, current),
+            ) = (left.as_ref(), current.as_ref())
+            {
+                if let Expression::StorageVariable(_, _, current_contract_no, current_var_no) =
+                    current.as_ref()
+                {
+                    if var_contract_no == current_contract_no
+                        && var_no == current_var_no
+                        && independent_of((*var_contract_no, *var_no), value)
+                    {
+                        return Some((left, value));
+                    }
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Does the expression neither refer to the given storage variable nor have side effects
+fn independent_of(var: (usize, usize), expr: &Expression) -> bool {
+    let mut independent = (var, true);
+
+    expr.recurse(&mut independent, |expr, (var, independent)| {
+        match expr {
+            Expression::StorageVariable(_, _, contract_no, var_no)
+                if (*contract_no, *var_no) == *var =>
+            {
+                *independent = false;
+            }
+            Expression::InternalFunctionCall { .. }
+            | Expression::ExternalFunctionCall { .. }
+            | Expression::ExternalFunctionCallRaw { .. }
+            | Expression::Constructor { .. }
+            | Expression::Assign(..)
+            | Expression::PreIncrement(..)
+            | Expression::PreDecrement(..)
+            | Expression::PostIncrement(..)
+            | Expression::PostDecrement(..)
+            | Expression::DynamicArrayPush(..)
+            | Expression::DynamicArrayPop(..)
+            // these are not recursed into, so whatever they contain is unknown
+            | Expression::FormatString(..)
+            | Expression::AbiEncode { .. } => {
+                *independent = false;
+            }
+            Expression::Builtin(_, _, builtin, _) => {
+                if matches!(
+                    builtin,
+                    Builtin::PayableSend
+                        | Builtin::PayableTransfer
+                        | Builtin::ArrayPush
+                        | Builtin::ArrayPop
+                        | Builtin::Revert
+                        | Builtin::Require
+                        | Builtin::SelfDestruct
+                ) {
+                    *independent = false;
+                }
+            }
+            _ => (),
+        }
+
+        *independent
+    });
+
+    independent.1
+}
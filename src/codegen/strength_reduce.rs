@@ -129,7 +129,8 @@ fn block_reduce(
                 *storage = expression_reduce(storage, &vars, ns);
                 *offset = expression_reduce(offset, &vars, ns);
             }
-            Instr::PushStorage { storage, value, .. } => {
+            Instr::PushStorage { storage, value, .. }
+            | Instr::AppendStorageBytes { storage, value } => {
                 *value = expression_reduce(value, &vars, ns);
                 *storage = expression_reduce(storage, &vars, ns);
             }
@@ -89,6 +89,7 @@ fn find_writable_vectors(
             | Instr::SetStorage { .. }
             | Instr::ClearStorage { .. }
             | Instr::SetStorageBytes { .. }
+            | Instr::AppendStorageBytes { .. }
             | Instr::PushStorage { .. }
             | Instr::PopStorage { .. }
             | Instr::SelfDestruct { .. }
@@ -99,6 +100,7 @@ fn find_writable_vectors(
             | Instr::Unreachable
             | Instr::Print { .. }
             | Instr::AssertFailure { .. }
+            | Instr::Panic { .. }
             | Instr::ValueTransfer { .. } => {
                 apply_transfers(&block.transfers[instr_no], vars, writable);
             }
@@ -2458,6 +2458,8 @@ impl<'a, 'b> EncoderBuilder<'a, 'b> {
 
 pub struct EthAbiDecoder {
     pub bswap: bool,
+    /// Reject non-canonical encodings of bool and uintN, i.e. words with high bits set
+    pub strict: bool,
 }
 
 impl EthAbiDecoder {
@@ -2492,23 +2494,36 @@ impl EthAbiDecoder {
             ty
         };
 
+        if self.strict {
+            self.check_canonical(binary, function, ty, data);
+        }
+
         match &ty {
             ast::Type::Bool => {
-                // solidity checks all the 32 bytes for being non-zero; we will just look at the upper 8 bytes, else we would need four loads
-                // which is unneeded (hopefully)
-                // cast to 64 bit pointer
+                // solidity checks all the 32 bytes for being non-zero. In strict mode anything
+                // but 0 or 1 has been rejected already, so just look at the upper 8 bytes
+                let word_type = if self.strict {
+                    binary.context.i64_type()
+                } else {
+                    binary.context.custom_width_int_type(256)
+                };
+
                 let bool_ptr = binary.builder.build_pointer_cast(
                     data,
-                    binary.context.i64_type().ptr_type(AddressSpace::Generic),
+                    word_type.ptr_type(AddressSpace::Generic),
                     "",
                 );
 
-                let bool_ptr = unsafe {
-                    binary.builder.build_gep(
-                        bool_ptr,
-                        &[binary.context.i32_type().const_int(3, false)],
-                        "bool_ptr",
-                    )
+                let bool_ptr = if self.strict {
+                    unsafe {
+                        binary.builder.build_gep(
+                            bool_ptr,
+                            &[binary.context.i32_type().const_int(3, false)],
+                            "bool_ptr",
+                        )
+                    }
+                } else {
+                    bool_ptr
                 };
 
                 let val = binary.builder.build_int_compare(
@@ -2517,7 +2532,7 @@ impl EthAbiDecoder {
                         .builder
                         .build_load(bool_ptr, "abi_bool")
                         .into_int_value(),
-                    binary.context.i64_type().const_zero(),
+                    word_type.const_zero(),
                     "bool",
                 );
                 if let Some(p) = to {
@@ -3202,6 +3217,73 @@ impl EthAbiDecoder {
         binary.builder.position_at_end(success_block);
     }
 
+    /// Check that the 32 byte word for a bool or uintN has no bits set beyond what the type
+    /// can hold; a bool must be 0 or 1. Other types are not checked.
+    fn check_canonical(
+        &self,
+        binary: &Binary,
+        function: FunctionValue,
+        ty: &ast::Type,
+        data: PointerValue,
+    ) {
+        let bits = match ty {
+            ast::Type::Bool => 1,
+            ast::Type::Uint(n) if *n < 256 => *n as u32,
+            _ => return,
+        };
+
+        let word_type = binary.context.custom_width_int_type(256);
+
+        let word = binary
+            .builder
+            .build_load(
+                binary.builder.build_pointer_cast(
+                    data,
+                    word_type.ptr_type(AddressSpace::Generic),
+                    "",
+                ),
+                "word",
+            )
+            .into_int_value();
+
+        // the word is big endian
+        let word = binary
+            .builder
+            .build_call(binary.llvm_bswap(256), &[word.into()], "")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        let high_bits = binary.builder.build_right_shift(
+            word,
+            word_type.const_int(bits as u64, false),
+            false,
+            "high_bits",
+        );
+
+        let canonical = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            high_bits,
+            word_type.const_zero(),
+            "canonical",
+        );
+
+        let success_block = binary.context.append_basic_block(function, "canonical");
+        let bail_block = binary.context.append_basic_block(function, "not_canonical");
+        binary
+            .builder
+            .build_conditional_branch(canonical, success_block, bail_block);
+
+        binary.builder.position_at_end(bail_block);
+
+        binary
+            .builder
+            .build_return(Some(&binary.return_values[&ReturnCode::AbiEncodingInvalid]));
+
+        binary.builder.position_at_end(success_block);
+    }
+
     /// abi decode the encoded data into the BasicValueEnums
     pub fn decode<'a>(
         &self,
@@ -3255,6 +3337,13 @@ pub fn encode_to_vector<'b>(
     bswap: bool,
     ns: &ast::Namespace,
 ) -> PointerValue<'b> {
+    // abi.encode() without arguments returns an allocated empty vector, not the null pointer
+    if packed.is_empty() && args.is_empty() {
+        let zero = binary.context.i32_type().const_zero();
+
+        return binary.vector_new(zero, binary.context.i32_type().const_int(1, false), None);
+    }
+
     let encoder = EncoderBuilder::new(binary, function, false, packed, args, tys, bswap, ns);
 
     let length = encoder.encoded_length();
@@ -1,4 +1,5 @@
 use crate::codegen::cfg::HashTy;
+use crate::codegen::Options;
 use crate::parser::pt;
 use crate::sema::ast;
 use std::cell::RefCell;
@@ -12,7 +13,6 @@ use inkwell::types::IntType;
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
-use inkwell::OptimizationLevel;
 use tiny_keccak::{Hasher, Keccak};
 
 use super::ethabiencoder;
@@ -29,22 +29,16 @@ impl EwasmTarget {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        opt: &Options,
     ) -> Binary<'a> {
         // first emit runtime code
         let mut b = EwasmTarget {
-            abi: ethabiencoder::EthAbiDecoder { bswap: false },
+            abi: ethabiencoder::EthAbiDecoder {
+                bswap: false,
+                strict: false,
+            },
         };
-        let mut runtime_code = Binary::new(
-            context,
-            ns.target,
-            &contract.name,
-            filename,
-            opt,
-            math_overflow_check,
-            None,
-        );
+        let mut runtime_code = Binary::new(context, ns.target, &contract.name, filename, opt, None);
 
         runtime_code.set_early_value_aborts(contract, ns);
 
@@ -63,7 +57,10 @@ impl EwasmTarget {
 
         // Now we have the runtime code, create the deployer
         let mut b = EwasmTarget {
-            abi: ethabiencoder::EthAbiDecoder { bswap: false },
+            abi: ethabiencoder::EthAbiDecoder {
+                bswap: false,
+                strict: false,
+            },
         };
         let mut deploy_code = Binary::new(
             context,
@@ -71,7 +68,6 @@ impl EwasmTarget {
             &contract.name,
             filename,
             opt,
-            math_overflow_check,
             Some(Box::new(runtime_code)),
         );
 
@@ -693,7 +689,7 @@ impl EwasmTarget {
             function,
             &binary.functions,
             None,
-            |func| !binary.function_abort_value_transfers && func.nonpayable,
+            |func| binary.function_rejects_value(func),
         );
     }
 
@@ -1230,19 +1226,8 @@ impl<'a> TargetRuntime<'a> for EwasmTarget {
     ) {
         let resolver_binary = &ns.contracts[contract_no];
 
-        let target_binary = Binary::build(
-            binary.context,
-            resolver_binary,
-            ns,
-            "",
-            binary.opt,
-            binary.math_overflow_check,
-        );
-
         // wasm
-        let wasm = target_binary
-            .code(Generate::Linked)
-            .expect("compile should succeeed");
+        let wasm = binary.contract_code(contract_no, false, ns);
 
         let code = binary.emit_global_string(
             &format!("contract_{}_code", resolver_binary.name),
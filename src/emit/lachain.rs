@@ -1,4 +1,5 @@
 use crate::codegen::cfg::HashTy;
+use crate::codegen::Options;
 use crate::parser::pt;
 use crate::sema::ast;
 use std::cell::RefCell;
@@ -8,19 +9,31 @@ use std::str;
 use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::context::Context;
 use inkwell::module::Linkage;
-use inkwell::types::IntType;
+use inkwell::types::{BasicType, IntType, StringRadix};
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
-use inkwell::OptimizationLevel;
+use num_bigint::BigInt;
+use num_traits::{One, ToPrimitive};
 use tiny_keccak::{Hasher, Keccak};
 
 use super::ethabiencoder;
 use super::{Binary, TargetRuntime, Variable};
-use crate::emit::Generate;
 
 pub struct LachainTarget {
     abi: ethabiencoder::EthAbiDecoder,
+    /// Storage slots of the immutable variables. Immutables are not kept in storage, but in a
+    /// region of 32 bytes per variable which is appended to the code on deployment. The region
+    /// ends with a 32 byte tag, see `immutables_tag()`.
+    immutables: Vec<BigInt>,
+    /// Return data beyond this many bytes is not copied, so a callee cannot make us run out
+    /// of memory by returning a huge buffer
+    max_return_data: Option<usize>,
+    /// Return data after a failed call, i.e. the revert data, beyond this many bytes is not
+    /// copied. Whether the last call failed is kept in the `call_failed` global
+    max_revert_data: Option<usize>,
+    /// Do not reverse the result of the hash host functions
+    hash_host_byte_order: bool,
 }
 
 impl LachainTarget {
@@ -29,42 +42,161 @@ impl LachainTarget {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        opt: &Options,
     ) -> Binary<'a> {
         // first emit runtime code
         let mut b = LachainTarget {
-            abi: ethabiencoder::EthAbiDecoder { bswap: false },
+            abi: ethabiencoder::EthAbiDecoder {
+                bswap: false,
+                strict: opt.strict_abi_decode,
+            },
+            immutables: contract
+                .layout
+                .iter()
+                .filter(|layout| ns.contracts[layout.contract_no].variables[layout.var_no].immutable)
+                .map(|layout| layout.slot.clone())
+                .collect(),
+            max_return_data: opt.max_return_data,
+            max_revert_data: opt.max_revert_data,
+            hash_host_byte_order: opt.hash_host_byte_order,
         };
-        let mut runtime_code = Binary::new(
-            context,
-            ns.target,
-            &contract.name,
-            filename,
-            opt,
-            math_overflow_check,
-            None,
-        );
+        let mut runtime_code = Binary::new(context, ns.target, &contract.name, filename, opt, None);
 
         runtime_code.set_early_value_aborts(contract, ns);
 
+        if opt.meter_gas {
+            runtime_code.gas_meter_blocks = Some(Vec::new());
+        }
+
         // externals
         b.declare_externals(&mut runtime_code);
 
-        // This also emits the constructors. We are relying on DCE to eliminate them from
-        // the final code.
+        if !b.immutables.is_empty() {
+            let mut initializer = vec![context.i8_type().const_zero(); b.immutables.len() * 32];
+
+            initializer.extend(
+                immutables_tag(contract)
+                    .iter()
+                    .map(|byte| context.i8_type().const_int(*byte as u64, false)),
+            );
+
+            let ty = context.i8_type().array_type(initializer.len() as u32);
+
+            let immutables =
+                runtime_code
+                    .module
+                    .add_global(ty, Some(AddressSpace::Generic), "immutables");
+
+            immutables.set_linkage(Linkage::Internal);
+            immutables.set_initializer(&context.i8_type().const_array(&initializer));
+        }
+
+        if b.max_revert_data.is_some() {
+            let call_failed = runtime_code.module.add_global(
+                context.bool_type(),
+                Some(AddressSpace::Generic),
+                "call_failed",
+            );
+
+            call_failed.set_linkage(Linkage::Internal);
+            call_failed.set_initializer(&context.bool_type().const_zero());
+        }
+
+        // This also emits the constructors, which are only reachable from deploy
         b.emit_functions(&mut runtime_code, contract, ns);
 
         b.function_dispatch(&runtime_code, contract, ns);
 
-        runtime_code.internalize(&["start"]);
+        b.deployer_dispatch(&runtime_code, contract, ns);
+
+        runtime_code.internalize(&["start", "deploy"]);
+
         runtime_code
     }
 
-    fn runtime_prelude<'a>(
+    /// Put the address on the stack and ask the host for the size of its code. Returns the
+    /// pointer to the address, for copying the code, and the size.
+    fn external_code_size<'a>(
+        binary: &Binary<'a>,
+        addr: IntValue<'a>,
+        ns: &ast::Namespace,
+    ) -> (PointerValue<'a>, IntValue<'a>) {
+        let address = binary
+            .builder
+            .build_alloca(binary.address_type(ns), "address");
+
+        binary.builder.build_store(address, addr);
+
+        let address = binary.builder.build_pointer_cast(
+            address,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "",
+        );
+
+        let length = binary
+            .builder
+            .build_call(
+                binary.module.get_function("get_external_code_size").unwrap(),
+                &[address.into()],
+                "code_size",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        (address, length)
+    }
+
+    /// Allocate a 256 bit storage word on the stack and zero it. Returns the word and the
+    /// same pointer cast to i8*, for passing to save_storage.
+    fn zeroed_storage_word<'a>(binary: &Binary<'a>) -> (PointerValue<'a>, PointerValue<'a>) {
+        let value = binary
+            .builder
+            .build_alloca(binary.context.custom_width_int_type(256), "value");
+
+        let value8 = binary.builder.build_pointer_cast(
+            value,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "value8",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("__bzero8").unwrap(),
+            &[
+                value8.into(),
+                binary.context.i32_type().const_int(4, false).into(),
+            ],
+            "",
+        );
+
+        (value, value8)
+    }
+
+    /// Does the type occupy a contiguous range of slots which can all be cleared by writing
+    /// zero, i.e. no mappings, strings or dynamic arrays
+    fn contiguous_storage(ty: &ast::Type, ns: &ast::Namespace) -> bool {
+        match ty.deref_any() {
+            ast::Type::Struct(n) => ns.structs[*n]
+                .fields
+                .iter()
+                .all(|field| LachainTarget::contiguous_storage(&field.ty, ns)),
+            ast::Type::Array(elem_ty, dims) => {
+                dims.iter().all(|d| d.is_some())
+                    && LachainTarget::contiguous_storage(elem_ty, ns)
+            }
+            ast::Type::Mapping(..) | ast::Type::String | ast::Type::DynamicBytes => false,
+            _ => true,
+        }
+    }
+
+    /// Copy the call data into memory. On deployment, the call data is the abi encoded
+    /// constructor arguments.
+    fn prelude<'a>(
         &self,
         binary: &Binary<'a>,
         function: FunctionValue,
+        abort_value_transfers: bool,
         ns: &ast::Namespace,
     ) -> (PointerValue<'a>, IntValue<'a>) {
         let entry = binary.context.append_basic_block(function, "entry");
@@ -72,7 +204,7 @@ impl LachainTarget {
         binary.builder.position_at_end(entry);
 
         // first thing to do is abort value transfers if we're not payable
-        if binary.function_abort_value_transfers {
+        if abort_value_transfers {
             self.abort_if_value_transfer(binary, function, ns);
         }
 
@@ -196,6 +328,31 @@ impl LachainTarget {
             Some(Linkage::External),
         );
 
+        binary.module.add_function(
+            "get_external_code_size",
+            u32_ty.fn_type(
+                &[
+                    u8_ptr_ty.into(), // addressOffset
+                ],
+                false,
+            ),
+            Some(Linkage::External),
+        );
+
+        binary.module.add_function(
+            "copy_external_code",
+            void_ty.fn_type(
+                &[
+                    u8_ptr_ty.into(), // addressOffset
+                    u8_ptr_ty.into(), // resultOffset
+                    u32_ty.into(),    // dataOffset
+                    u32_ty.into(),    // length
+                ],
+                false,
+            ),
+            Some(Linkage::External),
+        );
+
         binary.module.add_function(
             "copy_call_value",
             void_ty.fn_type(
@@ -324,6 +481,17 @@ impl LachainTarget {
             Some(Linkage::External),
         );
 
+        binary.module.add_function(
+            "meter_gas",
+            void_ty.fn_type(
+                &[
+                    u32_ty.into(), // block id
+                ],
+                false,
+            ),
+            Some(Linkage::External),
+        );
+
         binary.module.add_function(
             "get_gas_left",
             void_ty.fn_type(
@@ -568,12 +736,20 @@ impl LachainTarget {
         contract: &ast::Contract,
         ns: &ast::Namespace,
     ) {
-        // create start function
-        let ret = binary.context.void_type();
-        let ftype = ret.fn_type(&[], false);
-        let function = binary.module.add_function("start", ftype, None);
+        // The abi decoder returns an error code from the function it is emitted in when the
+        // calldata is invalid, so the dispatch is done in a function which returns i32
+        let function = binary.module.add_function(
+            "sol::dispatch",
+            binary.context.i32_type().fn_type(&[], false),
+            Some(Linkage::Internal),
+        );
+
+        let (argsdata, argslen) =
+            self.prelude(binary, function, binary.function_abort_value_transfers, ns);
 
-        let (argsdata, argslen) = self.runtime_prelude(binary, function, ns);
+        if !self.immutables.is_empty() {
+            self.load_immutables(binary, function, contract, ns);
+        }
 
         self.emit_function_dispatch(
             binary,
@@ -585,8 +761,293 @@ impl LachainTarget {
             function,
             &binary.functions,
             None,
-            |func| !binary.function_abort_value_transfers && func.nonpayable,
+            |func| binary.function_rejects_value(func),
+        );
+
+        self.entrypoint(binary, "start", function);
+    }
+
+    /// The deploy function is called once when the contract is created. It runs the storage
+    /// initializers and the constructor, with the arguments decoded from the call data.
+    fn deployer_dispatch(
+        &mut self,
+        binary: &Binary,
+        contract: &ast::Contract,
+        ns: &ast::Namespace,
+    ) {
+        let function = binary.module.add_function(
+            "sol::deployer",
+            binary.context.i32_type().fn_type(&[], false),
+            Some(Linkage::Internal),
+        );
+
+        let (argsdata, argslen) =
+            self.prelude(binary, function, binary.constructor_abort_value_transfers, ns);
+
+        // init our storage vars
+        if let Some(initializer) = contract.initializer {
+            binary
+                .builder
+                .build_call(binary.functions[&initializer], &[], "");
+        }
+
+        // base constructors are called from the constructor of this contract, and they are not
+        // public, hence find()
+        if let Some((cfg_no, cfg)) = contract
+            .cfg
+            .iter()
+            .enumerate()
+            .find(|(_, cfg)| cfg.ty == pt::FunctionTy::Constructor && cfg.public)
+        {
+            let mut args = Vec::new();
+
+            // insert abi decode
+            self.abi.decode(
+                binary,
+                function,
+                &mut args,
+                argsdata,
+                argslen,
+                &cfg.params,
+                ns,
+            );
+
+            binary
+                .builder
+                .build_call(binary.functions[&cfg_no], &args, "");
+        }
+
+        if self.immutables.is_empty() {
+            self.return_empty_abi(binary);
+        } else {
+            // the host appends the returned immutables to the code of the contract
+            let (region, length) = self.immutables_region(binary);
+
+            self.return_abi(binary, region, length);
+        }
+
+        self.entrypoint(binary, "deploy", function);
+    }
+
+    /// The immutables region and its length in bytes, including the tag
+    fn immutables_region<'a>(&self, binary: &Binary<'a>) -> (PointerValue<'a>, IntValue<'a>) {
+        let region = binary.builder.build_pointer_cast(
+            binary
+                .module
+                .get_global("immutables")
+                .unwrap()
+                .as_pointer_value(),
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "immutables",
+        );
+
+        let length = binary
+            .context
+            .i32_type()
+            .const_int((self.immutables.len() as u64 + 1) * 32, false);
+
+        (region, length)
+    }
+
+    /// Copy the immutables from the end of the code at our address. Under delegatecall, this is
+    /// the code of the caller, and there is no host function for reading the code which is
+    /// executing. So, check that the code ends with our tag, and abort if it does not.
+    fn load_immutables<'a>(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+        contract: &ast::Contract,
+        ns: &ast::Namespace,
+    ) {
+        let (region, length) = self.immutables_region(binary);
+
+        let address = binary
+            .builder
+            .build_alloca(binary.address_type(ns), "address");
+
+        let address = binary.builder.build_pointer_cast(
+            address,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "",
+        );
+
+        binary.builder.build_call(
+            binary.module.get_function("get_address").unwrap(),
+            &[address.into()],
+            "",
+        );
+
+        let code_size = binary
+            .builder
+            .build_call(
+                binary
+                    .module
+                    .get_function("get_external_code_size")
+                    .unwrap(),
+                &[address.into()],
+                "code_size",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        let copy_block = binary
+            .context
+            .append_basic_block(function, "copy_immutables");
+        let bad_block = binary
+            .context
+            .append_basic_block(function, "bad_immutables");
+        let done_block = binary
+            .context
+            .append_basic_block(function, "immutables_loaded");
+
+        // the subtraction below must not underflow
+        let too_short =
+            binary
+                .builder
+                .build_int_compare(IntPredicate::ULT, code_size, length, "too_short");
+
+        binary
+            .builder
+            .build_conditional_branch(too_short, bad_block, copy_block);
+
+        binary.builder.position_at_end(copy_block);
+
+        binary.builder.build_call(
+            binary.module.get_function("copy_external_code").unwrap(),
+            &[
+                address.into(),
+                region.into(),
+                binary
+                    .builder
+                    .build_int_sub(code_size, length, "offset")
+                    .into(),
+                length.into(),
+            ],
+            "",
+        );
+
+        let tag = binary.emit_global_string("immutables_tag", &immutables_tag(contract), true);
+
+        let copied_tag = unsafe {
+            binary.builder.build_gep(
+                region,
+                &[binary
+                    .context
+                    .i32_type()
+                    .const_int(self.immutables.len() as u64 * 32, false)],
+                "copied_tag",
+            )
+        };
+
+        let tag_len = binary.context.i32_type().const_int(32, false);
+
+        let tag_matches = binary
+            .builder
+            .build_call(
+                binary.module.get_function("__memcmp").unwrap(),
+                &[
+                    copied_tag.into(),
+                    tag_len.into(),
+                    tag.into(),
+                    tag_len.into(),
+                ],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        binary
+            .builder
+            .build_conditional_branch(tag_matches, done_block, bad_block);
+
+        binary.builder.position_at_end(bad_block);
+
+        self.assert_failure(
+            binary,
+            binary
+                .context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .const_null(),
+            binary.context.i32_type().const_zero(),
         );
+
+        binary.builder.position_at_end(done_block);
+    }
+
+    /// If the storage slot is constant and belongs to an immutable, return a pointer to its
+    /// value in the immutables region
+    fn immutable<'a>(
+        &self,
+        binary: &Binary<'a>,
+        ty: &ast::Type,
+        slot: IntValue<'a>,
+        ns: &ast::Namespace,
+    ) -> Option<PointerValue<'a>> {
+        if !slot.is_const() {
+            return None;
+        }
+
+        let no = self.immutables.iter().position(|immutable| {
+            slot == slot
+                .get_type()
+                .const_int_from_string(&immutable.to_string(), StringRadix::Decimal)
+                .unwrap()
+        })?;
+
+        let (region, _) = self.immutables_region(binary);
+
+        let offset = unsafe {
+            binary.builder.build_gep(
+                region,
+                &[binary.context.i32_type().const_int(no as u64 * 32, false)],
+                "immutable",
+            )
+        };
+
+        Some(binary.builder.build_pointer_cast(
+            offset,
+            binary.llvm_type(ty, ns).ptr_type(AddressSpace::Generic),
+            "",
+        ))
+    }
+
+    /// Create an exported function which calls the given dispatch function. Every successful
+    /// call halts, so if the dispatch function returns the call data could not be decoded.
+    fn entrypoint(&self, binary: &Binary, name: &str, dispatch: FunctionValue) {
+        let function = binary
+            .module
+            .add_function(name, binary.context.void_type().fn_type(&[], false), None);
+
+        binary
+            .builder
+            .position_at_end(binary.context.append_basic_block(function, "entry"));
+
+        let ret = binary
+            .builder
+            .build_call(dispatch, &[], "")
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        self.return_code(binary, ret);
+    }
+
+    /// Remember whether the last call failed, so that return_data() knows to apply the
+    /// revert data cap. Nothing is stored if there is no revert data cap.
+    fn set_call_failed(&self, binary: &Binary, is_success: IntValue) {
+        if let Some(call_failed) = binary.module.get_global("call_failed") {
+            let failed = binary.builder.build_not(is_success, "failed");
+
+            binary
+                .builder
+                .build_store(call_failed.as_pointer_value(), failed);
+        }
     }
 
     fn encode<'b>(
@@ -662,30 +1123,103 @@ impl LachainTarget {
 }
 
 impl<'a> TargetRuntime<'a> for LachainTarget {
-    fn storage_delete_single_slot(
+    fn storage_load(
         &self,
-        binary: &Binary,
-        _function: FunctionValue,
-        slot: PointerValue,
+        binary: &Binary<'a>,
+        ty: &ast::Type,
+        slot: &mut IntValue<'a>,
+        function: FunctionValue,
+        ns: &ast::Namespace,
+    ) -> BasicValueEnum<'a> {
+        if let Some(immutable) = self.immutable(binary, ty, *slot, ns) {
+            return binary.builder.build_load(immutable, "immutable");
+        }
+
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+
+        self.storage_load_slot(binary, ty, slot, slot_ptr, function, ns)
+    }
+
+    fn storage_store(
+        &self,
+        binary: &Binary<'a>,
+        ty: &ast::Type,
+        slot: &mut IntValue<'a>,
+        dest: BasicValueEnum<'a>,
+        function: FunctionValue<'a>,
+        ns: &ast::Namespace,
     ) {
-        let value = binary
-            .builder
-            .build_alloca(binary.context.custom_width_int_type(256), "value");
+        if let Some(immutable) = self.immutable(binary, ty, *slot, ns) {
+            binary.builder.build_store(immutable, dest);
+            return;
+        }
 
-        let value8 = binary.builder.build_pointer_cast(
-            value,
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+
+        self.storage_store_slot(binary, ty, slot, slot_ptr, dest, function, ns)
+    }
+
+    fn storage_delete(
+        &self,
+        binary: &Binary<'a>,
+        ty: &ast::Type,
+        slot: &mut IntValue<'a>,
+        function: FunctionValue<'a>,
+        ns: &ast::Namespace,
+    ) {
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+
+        let slots = ty.storage_slots(ns);
+
+        if slots <= BigInt::one() || !LachainTarget::contiguous_storage(ty, ns) {
+            self.storage_delete_slot(binary, ty, slot, slot_ptr, function, ns);
+            return;
+        }
+
+        // rather than zeroing a word for each member, share one zero word and loop over
+        // the slots
+        let (_, value8) = LachainTarget::zeroed_storage_word(binary);
+
+        let slot_ptr8 = binary.builder.build_pointer_cast(
+            slot_ptr,
             binary.context.i8_type().ptr_type(AddressSpace::Generic),
-            "value8",
+            "",
         );
 
-        binary.builder.build_call(
-            binary.module.get_function("__bzero8").unwrap(),
-            &[
-                value8.into(),
-                binary.context.i32_type().const_int(4, false).into(),
-            ],
-            "",
+        binary.emit_static_loop_with_int(
+            function,
+            binary.context.i64_type().const_zero(),
+            binary
+                .context
+                .i64_type()
+                .const_int(slots.to_u64().unwrap(), false),
+            slot,
+            |_index: IntValue<'a>, slot: &mut IntValue<'a>| {
+                binary.builder.build_store(slot_ptr, *slot);
+
+                binary.builder.build_call(
+                    binary.module.get_function("save_storage").unwrap(),
+                    &[slot_ptr8.into(), value8.into()],
+                    "",
+                );
+
+                *slot = binary.builder.build_int_add(
+                    *slot,
+                    slot.get_type().const_int(1, false),
+                    "next_slot",
+                );
+            },
         );
+    }
+
+    fn storage_delete_single_slot(
+        &self,
+        binary: &Binary,
+        _function: FunctionValue,
+        slot: PointerValue,
+    ) {
+        // the old value is never loaded; a zero word is written over it
+        let (_, value8) = LachainTarget::zeroed_storage_word(binary);
 
         binary.builder.build_call(
             binary.module.get_function("save_storage").unwrap(),
@@ -835,69 +1369,194 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             )
         };
 
+        // an uninitialized storage string has length 0; do not ask the host to copy
+        // into a zero length buffer
+        let is_empty = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            length,
+            binary.context.i32_type().const_zero(),
+            "is_empty",
+        );
+
+        let load_block = binary.context.append_basic_block(function, "load_string");
+        let done_block = binary.context.append_basic_block(function, "string_loaded");
+
+        binary
+            .builder
+            .build_conditional_branch(is_empty, done_block, load_block);
+
+        binary.builder.position_at_end(load_block);
+
+        binary.builder.build_call(
+            binary.module.get_function("load_storage_string").unwrap(),
+            &[
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        slot,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+                binary
+                    .builder
+                    .build_pointer_cast(
+                        string,
+                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                        "",
+                    )
+                    .into(),
+            ],
+            "",
+        );
+
+        binary.builder.build_unconditional_branch(done_block);
+
+        binary.builder.position_at_end(done_block);
+
+        v
+    }
+
+    fn set_storage_extfunc(
+        &self,
+        _binary: &Binary,
+        _function: FunctionValue,
+        _slot: PointerValue,
+        _dest: PointerValue,
+    ) {
+        unimplemented!();
+    }
+    fn get_storage_extfunc(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: PointerValue<'a>,
+        _ns: &ast::Namespace,
+    ) -> PointerValue<'a> {
+        unimplemented!();
+    }
+    fn get_storage_bytes_subscript(
+        &self,
+        _binary: &Binary<'a>,
+        _function: FunctionValue,
+        _slot: IntValue<'a>,
+        _index: IntValue<'a>,
+    ) -> IntValue<'a> {
+        unimplemented!();
+    }
+    fn set_storage_bytes_subscript(
+        &self,
+        _binary: &Binary,
+        _function: FunctionValue,
+        _slot: IntValue,
+        _index: IntValue,
+        _val: IntValue,
+    ) {
+        unimplemented!();
+    }
+
+    /// Append to bytes or string in storage: the old contents are loaded straight into a
+    /// buffer large enough for both, so only one allocation and one save are needed
+    fn storage_append_bytes(
+        &self,
+        binary: &Binary<'a>,
+        function: FunctionValue<'a>,
+        slot: IntValue<'a>,
+        value: BasicValueEnum<'a>,
+    ) {
+        let slot_ptr = binary.builder.build_alloca(slot.get_type(), "slot");
+
+        binary.builder.build_store(slot_ptr, slot);
+
+        let slot_ptr = binary.builder.build_pointer_cast(
+            slot_ptr,
+            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+            "",
+        );
+
+        let old_length = binary
+            .builder
+            .build_call(
+                binary.module.get_function("get_storage_string_size").unwrap(),
+                &[slot_ptr.into()],
+                "storagestringsize",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_int_value();
+
+        let extra_length = binary.vector_len(value);
+
+        let length = binary
+            .builder
+            .build_int_add(old_length, extra_length, "length");
+
+        let data = binary
+            .builder
+            .build_call(
+                binary.module.get_function("__malloc").unwrap(),
+                &[length.into()],
+                "",
+            )
+            .try_as_basic_value()
+            .left()
+            .unwrap()
+            .into_pointer_value();
+
+        // do not ask the host to copy an uninitialized storage string
+        let is_empty = binary.builder.build_int_compare(
+            IntPredicate::EQ,
+            old_length,
+            binary.context.i32_type().const_zero(),
+            "is_empty",
+        );
+
+        let load_block = binary.context.append_basic_block(function, "load_string");
+        let append_block = binary.context.append_basic_block(function, "append_string");
+
+        binary
+            .builder
+            .build_conditional_branch(is_empty, append_block, load_block);
+
+        binary.builder.position_at_end(load_block);
+
         binary.builder.build_call(
             binary.module.get_function("load_storage_string").unwrap(),
+            &[slot_ptr.into(), data.into()],
+            "",
+        );
+
+        binary.builder.build_unconditional_branch(append_block);
+
+        binary.builder.position_at_end(append_block);
+
+        let dest = unsafe { binary.builder.build_gep(data, &[old_length], "dest") };
+
+        binary.builder.build_call(
+            binary.module.get_function("__memcpy").unwrap(),
             &[
+                dest.into(),
                 binary
                     .builder
                     .build_pointer_cast(
-                        slot,
-                        binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                        "",
-                    )
-                    .into(),
-                binary
-                    .builder
-                    .build_pointer_cast(
-                        string,
+                        binary.vector_bytes(value),
                         binary.context.i8_type().ptr_type(AddressSpace::Generic),
                         "",
                     )
                     .into(),
+                extra_length.into(),
             ],
             "",
         );
 
-        v
+        binary.builder.build_call(
+            binary.module.get_function("save_storage_string").unwrap(),
+            &[slot_ptr.into(), data.into(), length.into()],
+            "",
+        );
     }
 
-    fn set_storage_extfunc(
-        &self,
-        _binary: &Binary,
-        _function: FunctionValue,
-        _slot: PointerValue,
-        _dest: PointerValue,
-    ) {
-        unimplemented!();
-    }
-    fn get_storage_extfunc(
-        &self,
-        _binary: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: PointerValue<'a>,
-        _ns: &ast::Namespace,
-    ) -> PointerValue<'a> {
-        unimplemented!();
-    }
-    fn get_storage_bytes_subscript(
-        &self,
-        _binary: &Binary<'a>,
-        _function: FunctionValue,
-        _slot: IntValue<'a>,
-        _index: IntValue<'a>,
-    ) -> IntValue<'a> {
-        unimplemented!();
-    }
-    fn set_storage_bytes_subscript(
-        &self,
-        _binary: &Binary,
-        _function: FunctionValue,
-        _slot: IntValue,
-        _index: IntValue,
-        _val: IntValue,
-    ) {
-        unimplemented!();
-    }
     fn storage_push(
         &self,
         _binary: &Binary<'a>,
@@ -957,24 +1616,7 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                 "",
             );
         } else {
-            let value = binary
-                .builder
-                .build_alloca(binary.context.custom_width_int_type(256), "value");
-
-            let value8 = binary.builder.build_pointer_cast(
-                value,
-                binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                "value8",
-            );
-
-            binary.builder.build_call(
-                binary.module.get_function("__bzero8").unwrap(),
-                &[
-                    value8.into(),
-                    binary.context.i32_type().const_int(4, false).into(),
-                ],
-                "",
-            );
+            let (value, value8) = LachainTarget::zeroed_storage_word(binary);
 
             let val = binary.builder.build_load(dest, "value");
 
@@ -1232,19 +1874,9 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
     ) {
         let resolver_binary = &ns.contracts[contract_no];
 
-        let target_binary = Binary::build(
-            binary.context,
-            resolver_binary,
-            ns,
-            "",
-            binary.opt,
-            binary.math_overflow_check,
-        );
-
-        // wasm
-        let wasm = target_binary
-            .code(Generate::Linked)
-            .expect("compile should succeeed");
+        // build_all() builds created contracts before their creators, so the code is
+        // usually there already and shared by every contract which creates it
+        let wasm = binary.contract_code(contract_no, false, ns);
 
         let code = binary.emit_global_string(
             &format!("contract_{}_code", resolver_binary.name),
@@ -1286,8 +1918,7 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             },
         );
 
-        let ret = binary.context.i32_type().const_zero();
-        if let Some(salt) = salt {
+        let ret = if let Some(salt) = salt {
             // salt is a u256
             let salt_ptr = binary
                 .builder
@@ -1295,7 +1926,7 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             binary.builder.build_store(salt_ptr, salt);
 
             // call create2
-            let ret = binary
+            binary
                 .builder
                 .build_call(
                     binary.module.get_function("create2").unwrap(),
@@ -1332,10 +1963,10 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                 .try_as_basic_value()
                 .left()
                 .unwrap()
-                .into_int_value();
+                .into_int_value()
         } else {
             // call create
-            let ret = binary
+            binary
                 .builder
                 .build_call(
                     binary.module.get_function("create").unwrap(),
@@ -1364,8 +1995,8 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                 .try_as_basic_value()
                 .left()
                 .unwrap()
-                .into_int_value();
-        }
+                .into_int_value()
+        };
 
         let is_success = binary.builder.build_int_compare(
             IntPredicate::EQ,
@@ -1374,6 +2005,8 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             "success",
         );
 
+        self.set_call_failed(binary, is_success);
+
         if let Some(success) = success {
             *success = is_success.into();
         } else {
@@ -1458,6 +2091,58 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             .build_alloca(binary.context.i64_type(), "gas");
         binary.builder.build_store(gas_ptr, gas);
 
+        // Like Solidity on EVM, high-level calls revert if the callee has no code. Low-level
+        // calls to an account without code succeed with empty return data. A try call also
+        // has a success flag; codegen checks for code before it, so the catch clause runs.
+        if success.is_none() {
+            let code_size = binary
+                .builder
+                .build_call(
+                    binary.module.get_function("get_external_code_size").unwrap(),
+                    &[binary
+                        .builder
+                        .build_pointer_cast(
+                            address.unwrap(),
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "address",
+                        )
+                        .into()],
+                    "code_size",
+                )
+                .try_as_basic_value()
+                .left()
+                .unwrap()
+                .into_int_value();
+
+            let has_code = binary.builder.build_int_compare(
+                IntPredicate::NE,
+                code_size,
+                binary.context.i32_type().const_zero(),
+                "has_code",
+            );
+
+            let call_block = binary.context.append_basic_block(function, "call");
+            let no_code_block = binary.context.append_basic_block(function, "no_code");
+
+            binary
+                .builder
+                .build_conditional_branch(has_code, call_block, no_code_block);
+
+            binary.builder.position_at_end(no_code_block);
+
+            self.assert_failure(
+                binary,
+                binary
+                    .context
+                    .i8_type()
+                    .ptr_type(AddressSpace::Generic)
+                    .const_null(),
+                binary.context.i32_type().const_zero(),
+            );
+
+            binary.builder.position_at_end(call_block);
+        }
+
         ret = binary
             .builder
             .build_call(
@@ -1511,6 +2196,10 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             "success",
         );
 
+        self.set_call_failed(binary, is_success);
+
+        // Low-level calls do not revert; the caller gets the success flag, and the return
+        // data (which is the revert reason on failure) is read afterwards with return_data()
         if let Some(success) = success {
             *success = is_success.into();
         } else {
@@ -1604,7 +2293,7 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                             binary.context.i8_type().ptr_type(AddressSpace::Generic),
                             "value_transfer",
                         )
-                        .into()
+                        .into(),
                 ],
                 "",
             )
@@ -1646,7 +2335,7 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
     }
 
     fn return_data<'b>(&self, binary: &Binary<'b>) -> PointerValue<'b> {
-        let length = binary
+        let mut length = binary
             .builder
             .build_call(
                 binary.module.get_function("get_return_size").unwrap(),
@@ -1658,6 +2347,52 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             .unwrap()
             .into_int_value();
 
+        if let Some(max) = self.max_return_data {
+            let max = binary.context.i32_type().const_int(max as u64, false);
+
+            let too_long = binary.builder.build_int_compare(
+                IntPredicate::UGT,
+                length,
+                max,
+                "too_long",
+            );
+
+            length = binary
+                .builder
+                .build_select(too_long, max, length, "returndatasize")
+                .into_int_value();
+        }
+
+        // after a failed call, the return data is revert data, which has a cap of its own
+        if let Some(max) = self.max_revert_data {
+            let failed = binary
+                .builder
+                .build_load(
+                    binary
+                        .module
+                        .get_global("call_failed")
+                        .unwrap()
+                        .as_pointer_value(),
+                    "call_failed",
+                )
+                .into_int_value();
+
+            let max = binary.context.i32_type().const_int(max as u64, false);
+
+            let too_long = binary.builder.build_and(
+                failed,
+                binary
+                    .builder
+                    .build_int_compare(IntPredicate::UGT, length, max, ""),
+                "too_long",
+            );
+
+            length = binary
+                .builder
+                .build_select(too_long, max, length, "returndatasize")
+                .into_int_value();
+        }
+
         let malloc_length = binary.builder.build_int_add(
             length,
             binary
@@ -1830,11 +2565,20 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
             "",
         );
 
+        let ty = binary.llvm_type(&ast::Type::Bytes(hashlen as u8), ns);
+
+        if self.hash_host_byte_order {
+            let hash = binary.builder.build_pointer_cast(
+                res,
+                ty.ptr_type(AddressSpace::Generic),
+                "hash",
+            );
+
+            return binary.builder.build_load(hash, "hash").into_int_value();
+        }
+
         // bytes32 needs to reverse bytes
-        let temp = binary.builder.build_alloca(
-            binary.llvm_type(&ast::Type::Bytes(hashlen as u8), ns),
-            "hash",
-        );
+        let temp = binary.builder.build_alloca(ty, "hash");
 
         binary.builder.build_call(
             binary.module.get_function("__beNtoleN").unwrap(),
@@ -1860,22 +2604,47 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
     fn send_event<'b>(
         &self,
         binary: &Binary<'b>,
-        event_no: usize,
+        _event_no: usize,
         data: PointerValue<'b>,
         data_len: IntValue<'b>,
-        topics: Vec<(PointerValue<'b>, IntValue<'b>)>,
-        ns: &ast::Namespace,
+        _topics: Vec<(PointerValue<'b>, IntValue<'b>)>,
+        _ns: &ast::Namespace,
     ) {
+        // the Lachain log has no topics, so only the data of the event is logged
         binary.builder.build_call(
             binary.module.get_function("write_log").unwrap(),
-            &[
-                data.into(),
-                data_len.into(),
-            ],
+            &[data.into(), data_len.into()],
             "",
         );
     }
 
+    /// Revert with `Panic(uint256)`, like Solidity does on EVM
+    fn panic_data<'b>(&self, binary: &Binary<'b>, code: u8) -> (PointerValue<'b>, IntValue<'b>) {
+        binary.panic_data(code)
+    }
+
+    /// Forwarding msg.data, e.g. from a proxy, passes on the calldata we were called with
+    fn forward_calldata<'b>(
+        &self,
+        binary: &Binary<'b>,
+    ) -> Option<(PointerValue<'b>, IntValue<'b>)> {
+        Some(binary.calldata_payload())
+    }
+
+    /// receive() is only for plain value transfers; any calldata goes to fallback()
+    fn receive_needs_empty_calldata(&self) -> bool {
+        true
+    }
+
+    fn fallback_rejects_value(&self) -> bool {
+        true
+    }
+
+    /// Internal functions which are called once, or are tiny, are cheaper inlined
+    fn inline_hints(&self, contract: &ast::Contract) -> Vec<bool> {
+        super::inline_hints(contract)
+    }
+
     /// builtin expressions
     fn builtin<'b>(
         &self,
@@ -1904,22 +2673,44 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                     $name,
                 );
 
+                binary.builder.build_load(value, $name)
+            }};
+            // The host may write fewer bytes than the width, so zero the value first
+            ($name:literal, $func:literal, $width:expr, zero_extend) => {{
+                let ty = binary.context.custom_width_int_type($width);
+                let value = binary.builder.build_alloca(ty, $name);
+
+                binary.builder.build_store(value, ty.const_zero());
+
+                binary.builder.build_call(
+                    binary.module.get_function($func).unwrap(),
+                    &[binary
+                        .builder
+                        .build_pointer_cast(
+                            value,
+                            binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                            "",
+                        )
+                        .into()],
+                    $name,
+                );
+
                 binary.builder.build_load(value, $name)
             }};
         }
 
         match expr {
             ast::Expression::Builtin(_, _, ast::Builtin::BlockNumber, _) => {
-                single_value_stack!("block_number", "get_block_number", 64)
+                single_value_stack!("block_number", "get_block_number", 256, zero_extend)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::GasLimit, _) => {
                 single_value_stack!("gas_limit", "get_block_gas_limit", 64)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::Timestamp, _) => {
-                single_value_stack!("time_stamp", "get_block_timestamp", 64)
+                single_value_stack!("time_stamp", "get_block_timestamp", 256, zero_extend)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::ChainId, _) => {
-                single_value_stack!("chain_id", "get_chain_id", 64)
+                single_value_stack!("chain_id", "get_chain_id", 256, zero_extend)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::BlockDifficulty, _) => {
                 single_value_stack!("block_difficulty", "get_block_difficulty", 256)
@@ -1928,16 +2719,23 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                 single_value_stack!("coinbase", "get_block_coinbase_address", ns.address_length as u32 * 8)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::Gasleft, _) => {
-                single_value_stack!("gas_left", "get_gas_left", 64)
+                single_value_stack!("gas_left", "get_gas_left", 256, zero_extend)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::Sender, _) => {
                 single_value_stack!("caller", "get_sender", ns.address_length as u32 * 8)
             }
             ast::Expression::Builtin(_, _, ast::Builtin::Value, _) => {
-                single_value_stack!("value", "get_msgvalue", ns.value_length as u32 * 8)
+                self.value_transferred(binary, ns).into()
             }
             ast::Expression::Builtin(_, _, ast::Builtin::Origin, _) => { 
-                single_value_stack!("origin", "get_tx_origin", ns.address_length as u32 * 8)
+                // the host writes a 20 byte address; zero first so the value is never
+                // left with stack garbage
+                single_value_stack!(
+                    "origin",
+                    "get_tx_origin",
+                    ns.address_length as u32 * 8,
+                    zero_extend
+                )
             }
             ast::Expression::Builtin(_, _, ast::Builtin::Gasprice, _) => { 
                 single_value_stack!("gas_price", "get_tx_gas_price", ns.value_length as u32 * 8)
@@ -1962,6 +2760,19 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
 
                 binary.builder.build_load(value, "self_address")
             }
+            ast::Expression::Builtin(_, _, ast::Builtin::BlobHash, args) => {
+                // there are no blobs on lachain; evaluate the index for its side effects only
+                self.expression(binary, &args[0], vartab, function, ns);
+
+                binary
+                    .context
+                    .custom_width_int_type(256)
+                    .const_zero()
+                    .into()
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::BlobBaseFee, _) => {
+                binary.context.custom_width_int_type(256).const_zero().into()
+            }
             ast::Expression::Builtin(_, _, ast::Builtin::BlockHash, args) => {
                 let block_number = self.expression(binary, &args[0], vartab, function, ns);
 
@@ -2039,79 +2850,131 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
 
                 binary.builder.build_load(balance, "balance")
             }
-            ast::Expression::Builtin(_, _, ast::Builtin::Ecrecover, args) => {
-                // hash
-                let hash_int = self
-                    .expression(binary, &args[0], vartab, function, ns)
+            ast::Expression::Builtin(_, _, ast::Builtin::ExternalCodeSize, addr) => {
+                let addr = self
+                    .expression(binary, &addr[0], vartab, function, ns)
+                    .into_int_value();
+
+                let (_, length) = LachainTarget::external_code_size(binary, addr, ns);
+
+                length.into()
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::ExternalCode, addr) => {
+                let addr = self
+                    .expression(binary, &addr[0], vartab, function, ns)
                     .into_int_value();
-                    
-                let hash = binary
+
+                let (address, length) = LachainTarget::external_code_size(binary, addr, ns);
+
+                // an address without code gives an empty vector
+                let v = binary.vector_new(
+                    length,
+                    binary.context.i32_type().const_int(1, false),
+                    None,
+                );
+
+                let data = binary.vector_bytes(v.into());
+
+                // do not ask the host to copy into a zero length buffer
+                let is_empty = binary.builder.build_int_compare(
+                    IntPredicate::EQ,
+                    length,
+                    binary.context.i32_type().const_zero(),
+                    "is_empty",
+                );
+
+                let copy_block = binary.context.append_basic_block(function, "copy_code");
+                let done_block = binary.context.append_basic_block(function, "code_copied");
+
+                binary
                     .builder
-                    .build_alloca(binary.value_type(ns), "hash");
-                
-                binary.builder.build_store(hash, hash_int);
+                    .build_conditional_branch(is_empty, done_block, copy_block);
+
+                binary.builder.position_at_end(copy_block);
+
+                binary.builder.build_call(
+                    binary.module.get_function("copy_external_code").unwrap(),
+                    &[
+                        address.into(),
+                        data.into(),
+                        binary.context.i32_type().const_zero().into(),
+                        length.into(),
+                    ],
+                    "",
+                );
+
+                binary.builder.build_unconditional_branch(done_block);
+
+                binary.builder.position_at_end(done_block);
+
+                v.into()
+            }
+            ast::Expression::Builtin(_, _, ast::Builtin::Ecrecover, args) => {
+                // hash, r and s are bytes32 which the host expects in big endian order
+                let be32 = |arg: &ast::Expression, name: &str| {
+                    let value = self.expression(binary, arg, vartab, function, ns);
+
+                    let le = binary.builder.build_alloca(value.get_type(), name);
+
+                    binary.builder.build_store(le, value);
+
+                    let be = binary.builder.build_array_alloca(
+                        binary.context.i8_type(),
+                        binary.context.i32_type().const_int(32, false),
+                        name,
+                    );
+
+                    binary.builder.build_call(
+                        binary.module.get_function("__beNtoleN").unwrap(),
+                        &[
+                            binary
+                                .builder
+                                .build_pointer_cast(
+                                    le,
+                                    binary.context.i8_type().ptr_type(AddressSpace::Generic),
+                                    name,
+                                )
+                                .into(),
+                            be.into(),
+                            binary.context.i32_type().const_int(32, false).into(),
+                        ],
+                        "",
+                    );
+
+                    be
+                };
+
+                let hash = be32(&args[0], "hash");
 
-                // v
                 let v = self
                     .expression(binary, &args[1], vartab, function, ns)
                     .into_int_value();
 
-                // r
-                let r_int = self
-                    .expression(binary, &args[2], vartab, function, ns)
-                    .into_int_value();
-                    
-                let r = binary
-                    .builder
-                    .build_alloca(binary.value_type(ns), "r");
-                
-                binary.builder.build_store(r, r_int);
+                let r = be32(&args[2], "r");
+                let s = be32(&args[3], "s");
 
-                // s
-                let s_int = self
-                    .expression(binary, &args[3], vartab, function, ns)
-                    .into_int_value();
-                    
-                let s = binary
-                    .builder
-                    .build_alloca(binary.value_type(ns), "s");
-                
-                binary.builder.build_store(s, s_int);
+                // the host writes the 20 byte address, or the zero address if the signature is
+                // invalid. Give it a zeroed word so that a host writing a full 32 bytes does not
+                // overrun the stack; only the first address_length bytes are loaded
+                let word_ty = binary.context.custom_width_int_type(256);
 
-                // result
-                let result = binary
-                    .builder
-                    .build_alloca(binary.address_type(ns), "result");
+                let word = binary.builder.build_alloca(word_ty, "result");
+
+                binary.builder.build_store(word, word_ty.const_zero());
+
+                let result = binary.builder.build_pointer_cast(
+                    word,
+                    binary.address_type(ns).ptr_type(AddressSpace::Generic),
+                    "result",
+                );
 
                 binary.builder.build_call(
                     binary.module.get_function("crypto_recover").unwrap(),
                     &[
-                        binary
-                            .builder
-                            .build_pointer_cast(
-                                hash,
-                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                                "hash",
-                            )
-                            .into(),
-                        v
-                            .into(),
-                        binary
-                            .builder
-                            .build_pointer_cast(
-                                r,
-                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                                "r",
-                            )
-                            .into(),
-                        binary
-                            .builder
-                            .build_pointer_cast(
-                                s,
-                                binary.context.i8_type().ptr_type(AddressSpace::Generic),
-                                "s",
-                            )
-                            .into(),
+                        hash.into(),
+                        v.into(),
+                        r.into(),
+                        s.into(),
                         binary
                             .builder
                             .build_pointer_cast(
@@ -2119,7 +2982,7 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
                                 binary.context.i8_type().ptr_type(AddressSpace::Generic),
                                 "result",
                             )
-                            .into()
+                            .into(),
                     ],
                     "result",
                 );
@@ -2130,3 +2993,15 @@ impl<'a> TargetRuntime<'a> for LachainTarget {
         }
     }
 }
+
+/// The immutables region is appended to the code on deployment, and ends with this tag. When
+/// the code at our address does not end with it, we are running under delegatecall from
+/// another contract, so its immutables are not ours.
+fn immutables_tag(contract: &ast::Contract) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(b"immutables:");
+    hasher.update(contract.name.as_bytes());
+    let mut hash = [0u8; 32];
+    hasher.finalize(&mut hash);
+    hash
+}
@@ -11,10 +11,13 @@ use std::str;
 use num_bigint::BigInt;
 use num_traits::One;
 use num_traits::ToPrimitive;
+use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 
 use crate::Target;
+use inkwell::attributes::{Attribute, AttributeLoc};
 use inkwell::builder::Builder;
 use inkwell::context::Context;
 use inkwell::memory_buffer::MemoryBuffer;
@@ -23,8 +26,8 @@ use inkwell::passes::PassManager;
 use inkwell::targets::{CodeModel, FileType, RelocMode, TargetTriple};
 use inkwell::types::{BasicType, BasicTypeEnum, FunctionType, IntType, StringRadix};
 use inkwell::values::{
-    ArrayValue, BasicValueEnum, CallableValue, FunctionValue, GlobalValue, IntValue, PhiValue,
-    PointerValue,
+    AnyValueEnum, ArrayValue, BasicValue, BasicValueEnum, CallableValue, FunctionValue,
+    GlobalValue, IntValue, PhiValue, PointerValue,
 };
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
@@ -40,8 +43,13 @@ mod solana;
 mod substrate;
 
 use crate::codegen::cfg::{ControlFlowGraph, HashTy, Instr, InternalCallTy, Storage};
+use crate::codegen::Options;
 use crate::linker::link;
 
+/// Internal functions with a single basic block of at most this many instructions are small
+/// enough that inlining them is always worth it
+const INLINE_MAX_INSTRUCTIONS: usize = 8;
+
 lazy_static::lazy_static! {
     static ref LLVM_INIT: () = {
         inkwell::targets::Target::initialize_webassembly(&Default::default());
@@ -171,6 +179,16 @@ pub trait TargetRuntime<'a> {
         index: IntValue<'a>,
         value: IntValue<'a>,
     );
+    fn storage_append_bytes(
+        &self,
+        _bin: &Binary<'a>,
+        _function: FunctionValue<'a>,
+        _slot: IntValue<'a>,
+        _value: BasicValueEnum<'a>,
+    ) {
+        // only generated for targets which store bytes as a single blob
+        unimplemented!();
+    }
     fn storage_subscript(
         &self,
         _bin: &Binary<'a>,
@@ -332,6 +350,40 @@ pub trait TargetRuntime<'a> {
         ns: &ast::Namespace,
     );
 
+    /// Revert data for a panic with the given code. By default, there is none
+    fn panic_data<'b>(&self, bin: &Binary<'b>, _code: u8) -> (PointerValue<'b>, IntValue<'b>) {
+        (
+            bin.context
+                .i8_type()
+                .ptr_type(AddressSpace::Generic)
+                .const_null(),
+            bin.context.i32_type().const_zero(),
+        )
+    }
+
+    /// If the calldata can be passed on as the payload of an external call without copying
+    /// it first, return its pointer and length
+    fn forward_calldata<'b>(&self, _bin: &Binary<'b>) -> Option<(PointerValue<'b>, IntValue<'b>)> {
+        None
+    }
+
+    /// Is receive() only called for value transfers without calldata, so that fallback()
+    /// handles everything else. By default, any value transfer goes to receive()
+    fn receive_needs_empty_calldata(&self) -> bool {
+        false
+    }
+
+    /// Does a fallback() which is not payable have to reject value transfers itself
+    fn fallback_rejects_value(&self) -> bool {
+        false
+    }
+
+    /// Which functions get the alwaysinline attribute, indexed by cfg number. By default,
+    /// inlining is left to llvm
+    fn inline_hints(&self, contract: &ast::Contract) -> Vec<bool> {
+        vec![false; contract.cfg.len()]
+    }
+
     /// Helper functions which need access to the trait
 
     /// If we receive a value transfer, and we are "payable", abort with revert
@@ -1152,28 +1204,7 @@ pub trait TargetRuntime<'a> {
                     .into()
             }
             Expression::CodeLiteral(_, bin_no, runtime) => {
-                let codegen_bin = &ns.contracts[*bin_no];
-
-                let target_bin = Binary::build(
-                    bin.context,
-                    codegen_bin,
-                    ns,
-                    "",
-                    bin.opt,
-                    bin.math_overflow_check,
-                );
-
-                let code = if *runtime && target_bin.runtime.is_some() {
-                    target_bin
-                        .runtime
-                        .unwrap()
-                        .code(Generate::Linked)
-                        .expect("compile should succeeed")
-                } else {
-                    target_bin
-                        .code(Generate::Linked)
-                        .expect("compile should succeeed")
-                };
+                let code = bin.contract_code(*bin_no, *runtime, ns);
 
                 let size = bin.context.i32_type().const_int(code.len() as u64, false);
 
@@ -3031,10 +3062,16 @@ pub trait TargetRuntime<'a> {
             bin.parameters = Some(function.get_last_param().unwrap().into_pointer_value());
         }
 
-        // Create all the stack variables
+        // Create all the stack variables. cfg.vars is a hash map, so visit the variables in
+        // order of their number; otherwise the allocas would be emitted in a different order
+        // on every compile
         let mut vars = HashMap::new();
 
-        for (no, v) in &cfg.vars {
+        let mut cfg_vars = cfg.vars.iter().collect::<Vec<_>>();
+
+        cfg_vars.sort_by_key(|(no, _)| **no);
+
+        for (no, v) in cfg_vars {
             match v.storage {
                 Storage::Local if v.ty.is_reference_type() && !v.ty.is_contract_storage() => {
                     // a null pointer means an empty, zero'ed thing, be it string, struct or array
@@ -3094,6 +3131,31 @@ pub trait TargetRuntime<'a> {
                 w.vars.get_mut(v).unwrap().value = (*phi).as_basic_value();
             }
 
+            if let Some(gas_meter_blocks) = &mut bin.gas_meter_blocks {
+                let block_id = gas_meter_blocks.len();
+
+                let loc = match cfg.function_no {
+                    Some(function_no) => ns.functions[function_no].loc,
+                    None => contract.loc,
+                };
+
+                gas_meter_blocks.push((
+                    cfg.name.to_owned(),
+                    cfg.blocks[w.block_no].name.to_owned(),
+                    loc,
+                ));
+
+                bin.builder.build_call(
+                    bin.module.get_function("meter_gas").unwrap(),
+                    &[bin
+                        .context
+                        .i32_type()
+                        .const_int(block_id as u64, false)
+                        .into()],
+                    "",
+                );
+            }
+
             for ins in &cfg.blocks[w.block_no].instr {
                 match ins {
                     Instr::Nop => (),
@@ -3252,6 +3314,15 @@ pub trait TargetRuntime<'a> {
                             value.into_int_value(),
                         );
                     }
+                    Instr::AppendStorageBytes { value, storage } => {
+                        let value = self.expression(bin, value, &w.vars, function, ns);
+
+                        let slot = self
+                            .expression(bin, storage, &w.vars, function, ns)
+                            .into_int_value();
+
+                        self.storage_append_bytes(bin, function, slot, value);
+                    }
                     Instr::PushStorage {
                         res,
                         storage,
@@ -3581,6 +3652,11 @@ pub trait TargetRuntime<'a> {
 
                         self.assert_failure(bin, data, len);
                     }
+                    Instr::Panic { code } => {
+                        let (data, len) = self.panic_data(bin, *code);
+
+                        self.assert_failure(bin, data, len);
+                    }
                     Instr::Print { expr } => {
                         let expr = self.expression(bin, expr, &w.vars, function, ns);
 
@@ -3814,7 +3890,19 @@ pub trait TargetRuntime<'a> {
                         let value = self
                             .expression(bin, value, &w.vars, function, ns)
                             .into_int_value();
-                        let payload = self.expression(bin, payload, &w.vars, function, ns);
+                        // forwarding msg.data, e.g. from a proxy; no need to copy it
+                        let forwarded = match payload {
+                            Expression::Builtin(_, _, Builtin::Calldata, _) => {
+                                self.forward_calldata(bin)
+                            }
+                            _ => None,
+                        };
+
+                        let (payload, payload_len) = forwarded.unwrap_or_else(|| {
+                            let payload = self.expression(bin, payload, &w.vars, function, ns);
+
+                            (bin.vector_bytes(payload), bin.vector_len(payload))
+                        });
 
                         let address = if let Some(address) = address {
                             let address = self.expression(bin, address, &w.vars, function, ns);
@@ -3850,8 +3938,8 @@ pub trait TargetRuntime<'a> {
                             bin,
                             function,
                             success,
-                            bin.vector_bytes(payload),
-                            bin.vector_len(payload),
+                            payload,
+                            payload_len,
                             address,
                             gas,
                             value,
@@ -4118,6 +4206,8 @@ pub trait TargetRuntime<'a> {
             )
         };
 
+        let calldata_len = argslen;
+
         let argslen =
             bin.builder
                 .build_int_sub(argslen, argslen.get_type().const_int(4, false), "argslen");
@@ -4185,16 +4275,33 @@ pub trait TargetRuntime<'a> {
             )
         };
 
+        let is_receive = if self.receive_needs_empty_calldata() {
+            let no_calldata = bin.builder.build_int_compare(
+                IntPredicate::EQ,
+                calldata_len,
+                calldata_len.get_type().const_zero(),
+                "no_calldata",
+            );
+
+            bin.builder.build_and(got_value, no_calldata, "is_receive")
+        } else {
+            got_value
+        };
+
         let fallback_block = bin.context.append_basic_block(function, "fallback");
         let receive_block = bin.context.append_basic_block(function, "receive");
 
         bin.builder
-            .build_conditional_branch(got_value, receive_block, fallback_block);
+            .build_conditional_branch(is_receive, receive_block, fallback_block);
 
         bin.builder.position_at_end(fallback_block);
 
         match fallback {
-            Some((cfg_no, _)) => {
+            Some((cfg_no, cfg)) => {
+                if self.fallback_rejects_value() && nonpayable(cfg) {
+                    self.abort_if_value_transfer(bin, function, ns);
+                }
+
                 bin.builder.build_call(bin.functions[&cfg_no], &[], "");
 
                 self.return_empty_abi(bin);
@@ -4369,6 +4476,14 @@ pub trait TargetRuntime<'a> {
     ) {
         let mut defines = Vec::new();
 
+        let inline = self.inline_hints(contract);
+
+        let alwaysinline = bin
+            .context
+            .create_enum_attribute(Attribute::get_named_enum_kind_id("alwaysinline"), 0);
+
+        // Functions are declared and defined in cfg order, so that repeated compilations
+        // of the same source produce identical code
         for (cfg_no, cfg) in contract.cfg.iter().enumerate() {
             if !cfg.is_placeholder() {
                 let ftype = bin.function_type(
@@ -4389,6 +4504,10 @@ pub trait TargetRuntime<'a> {
                     .module
                     .add_function(&cfg.name, ftype, Some(Linkage::Internal));
 
+                if inline[cfg_no] {
+                    func_decl.add_attribute(AttributeLoc::Function, alwaysinline);
+                }
+
                 bin.functions.insert(cfg_no, func_decl);
 
                 defines.push((func_decl, cfg));
@@ -5183,14 +5302,10 @@ pub trait TargetRuntime<'a> {
 
         bin.builder.position_at_end(error_block);
 
-        self.assert_failure(
-            bin,
-            bin.context
-                .i8_type()
-                .ptr_type(AddressSpace::Generic)
-                .const_null(),
-            bin.context.i32_type().const_zero(),
-        );
+        // Panic(0x11): arithmetic overflow or underflow
+        let (data, len) = self.panic_data(bin, 0x11);
+
+        self.assert_failure(bin, data, len);
 
         bin.builder.position_at_end(success_block);
 
@@ -5213,6 +5328,8 @@ pub struct Binary<'a> {
     functions: HashMap<usize, FunctionValue<'a>>,
     code: RefCell<Vec<u8>>,
     opt: OptimizationLevel,
+    /// The options this binary was built with, so that contracts it creates are built the same
+    options: Options,
     code_size: RefCell<Option<IntValue<'a>>>,
     selector: GlobalValue<'a>,
     calldata_data: GlobalValue<'a>,
@@ -5221,6 +5338,18 @@ pub struct Binary<'a> {
     scratch: Option<GlobalValue<'a>>,
     parameters: Option<PointerValue<'a>>,
     return_values: HashMap<ReturnCode, IntValue<'a>>,
+    /// When set, every basic block starts with a call to `meter_gas`, and the block ids index
+    /// this list of (function, basic block, location)
+    gas_meter_blocks: Option<Vec<(String, String, pt::Loc)>>,
+}
+
+/// Entry in the table mapping `meter_gas` block ids to source locations
+#[derive(Serialize)]
+struct GasMeterBlock {
+    id: usize,
+    function: String,
+    block: String,
+    location: String,
 }
 
 #[derive(PartialEq, Eq, Hash)]
@@ -5237,6 +5366,94 @@ pub enum Generate {
     Linked,
 }
 
+/// Build the code for all the concrete contracts in the namespace. Each contract is built once,
+/// and contracts are built before the contracts which create them, so that every contract which
+/// creates another embeds the code which was built here rather than building it again.
+pub fn build_all(ns: &mut ast::Namespace, opt: &Options) {
+    // Solana creates a single bundle
+    if ns.target == Target::Solana || ns.target == Target::Generic {
+        return;
+    }
+
+    let mut contracts_done = vec![false; ns.contracts.len()];
+
+    while contracts_done.iter().any(|e| !*e) {
+        for contract_no in 0..ns.contracts.len() {
+            if contracts_done[contract_no] {
+                continue;
+            }
+
+            if !ns.contracts[contract_no].is_concrete() {
+                contracts_done[contract_no] = true;
+                continue;
+            }
+
+            // does this contract create any contract which are not built yet
+            if ns.contracts[contract_no]
+                .creates
+                .iter()
+                .any(|c| !contracts_done[*c])
+            {
+                continue;
+            }
+
+            let context = Context::create();
+
+            let filename = ns.files[0].path.to_string_lossy();
+
+            let binary = ns.contracts[contract_no].emit(ns, &context, &filename, opt);
+
+            let code = binary.code(Generate::Linked).expect("llvm build");
+
+            let payable = binary.payable_under_value_abort(&ns.contracts[contract_no]);
+
+            drop(binary);
+
+            for function_no in payable {
+                let func = &ns.functions[function_no];
+
+                let message = if func.name.is_empty() {
+                    format!(
+                        "{} is payable, but contract ‘{}’ rejects all value transfers",
+                        func.ty, ns.contracts[contract_no].name
+                    )
+                } else {
+                    format!(
+                        "{} ‘{}’ is payable, but contract ‘{}’ rejects all value transfers",
+                        func.ty, func.name, ns.contracts[contract_no].name
+                    )
+                };
+
+                ns.diagnostics
+                    .push(ast::Diagnostic::warning(func.loc, message));
+            }
+
+            if let Some(limit) = opt.contract_size_limit_for(ns.target) {
+                if code.len() > limit {
+                    let contract = &ns.contracts[contract_no];
+
+                    let message = format!(
+                        "contract ‘{}’ code size of {} bytes exceeds limit of {} bytes",
+                        contract.name,
+                        code.len(),
+                        limit
+                    );
+
+                    ns.diagnostics.push(if opt.contract_size_limit_error {
+                        ast::Diagnostic::error(contract.loc, message)
+                    } else {
+                        ast::Diagnostic::warning(contract.loc, message)
+                    });
+                }
+            }
+
+            ns.contracts[contract_no].code = code;
+
+            contracts_done[contract_no] = true;
+        }
+    }
+}
+
 impl<'a> Binary<'a> {
     /// Build the LLVM IR for a single contract
     pub fn build(
@@ -5244,43 +5461,21 @@ impl<'a> Binary<'a> {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        options: &Options,
     ) -> Self {
         match ns.target {
-            Target::Substrate => substrate::SubstrateTarget::build(
-                context,
-                contract,
-                ns,
-                filename,
-                opt,
-                math_overflow_check,
-            ),
-            Target::Ewasm => {
-                ewasm::EwasmTarget::build(context, contract, ns, filename, opt, math_overflow_check)
+            Target::Substrate => {
+                substrate::SubstrateTarget::build(context, contract, ns, filename, options)
             }
+            Target::Ewasm => ewasm::EwasmTarget::build(context, contract, ns, filename, options),
             Target::Lachain => {
-                lachain::LachainTarget::build(context, contract, ns, filename, opt, math_overflow_check)
+                lachain::LachainTarget::build(context, contract, ns, filename, options)
             }
-            Target::Sabre => {
-                sabre::SabreTarget::build(context, contract, ns, filename, opt, math_overflow_check)
+            Target::Sabre => sabre::SabreTarget::build(context, contract, ns, filename, options),
+            Target::Generic => {
+                generic::GenericTarget::build(context, contract, ns, filename, options)
             }
-            Target::Generic => generic::GenericTarget::build(
-                context,
-                contract,
-                ns,
-                filename,
-                opt,
-                math_overflow_check,
-            ),
-            Target::Solana => solana::SolanaTarget::build(
-                context,
-                contract,
-                ns,
-                filename,
-                opt,
-                math_overflow_check,
-            ),
+            Target::Solana => solana::SolanaTarget::build(context, contract, ns, filename, options),
         }
     }
 
@@ -5301,12 +5496,9 @@ impl<'a> Binary<'a> {
     /// cached, since this function can be called multiple times (e.g. one for
     /// each time a bin of this type is created).
     /// Pass our module to llvm for optimization and compilation
-    pub fn code(&self, generate: Generate) -> Result<Vec<u8>, String> {
-        // return cached result if available
-        if !self.code.borrow().is_empty() {
-            return Ok(self.code.borrow().clone());
-        }
-
+    /// Run the llvm optimization passes for the optimization level on the module. This is
+    /// done when generating code, and can be done earlier to inspect the optimized module.
+    pub fn optimize(&self) {
         match self.opt {
             OptimizationLevel::Default | OptimizationLevel::Aggressive => {
                 let pass_manager = PassManager::create(());
@@ -5317,9 +5509,98 @@ impl<'a> Binary<'a> {
                 pass_manager.add_constant_merge_pass();
 
                 pass_manager.run_on(&self.module);
+
+                debug_assert!(
+                    self.unreachable_functions().is_empty(),
+                    "functions {:?} are not reachable but were not removed",
+                    self.unreachable_functions()
+                );
             }
             _ => {}
         }
+    }
+
+    /// After global dce, every function left in the module should be reachable from one of the
+    /// exported functions. Any internal function which is left but cannot be reached means
+    /// internalize() did not mark the right roots, and dead code ends up in the final output.
+    fn unreachable_functions(&self) -> Vec<String> {
+        let mut live = HashSet::new();
+
+        let mut func = self.module.get_first_function();
+
+        while let Some(f) = func {
+            if f.get_linkage() != Linkage::Internal {
+                live.insert(f.get_name().to_string_lossy().to_string());
+            }
+
+            func = f.get_next_function();
+        }
+
+        // find functions which are used from a live function, until nothing changes
+        loop {
+            let mut changed = false;
+
+            let mut func = self.module.get_first_function();
+
+            while let Some(f) = func {
+                let name = f.get_name().to_string_lossy().to_string();
+
+                if !live.contains(&name) {
+                    let mut next_use = f.as_global_value().as_pointer_value().get_first_use();
+
+                    while let Some(u) = next_use {
+                        let used_from_live = match u.get_user() {
+                            AnyValueEnum::InstructionValue(instr) => instr
+                                .get_parent()
+                                .and_then(|bb| bb.get_parent())
+                                .map(|f| live.contains(f.get_name().to_string_lossy().as_ref()))
+                                .unwrap_or(false),
+                            // used from a constant or global initializer; assume it is live
+                            _ => true,
+                        };
+
+                        if used_from_live {
+                            live.insert(name);
+                            changed = true;
+                            break;
+                        }
+
+                        next_use = u.get_next_use();
+                    }
+                }
+
+                func = f.get_next_function();
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let mut unreachable = Vec::new();
+
+        let mut func = self.module.get_first_function();
+
+        while let Some(f) = func {
+            let name = f.get_name().to_string_lossy().to_string();
+
+            if !live.contains(&name) {
+                unreachable.push(name);
+            }
+
+            func = f.get_next_function();
+        }
+
+        unreachable
+    }
+
+    pub fn code(&self, generate: Generate) -> Result<Vec<u8>, String> {
+        // return cached result if available
+        if !self.code.borrow().is_empty() {
+            return Ok(self.code.borrow().clone());
+        }
+
+        self.optimize();
 
         let target = inkwell::targets::Target::from_name(self.target.llvm_target_name()).unwrap();
 
@@ -5412,8 +5693,7 @@ impl<'a> Binary<'a> {
         target: Target,
         name: &str,
         filename: &str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        options: &Options,
         runtime: Option<Box<Binary<'a>>>,
     ) -> Self {
         lazy_static::initialize(&LLVM_INIT);
@@ -5472,13 +5752,14 @@ impl<'a> Binary<'a> {
             runtime,
             function_abort_value_transfers: false,
             constructor_abort_value_transfers: false,
-            math_overflow_check,
+            math_overflow_check: options.math_overflow_check,
             builder: context.create_builder(),
             context,
             target,
             functions: HashMap::new(),
             code: RefCell::new(Vec::new()),
-            opt,
+            opt: options.opt_level_for(name),
+            options: options.clone(),
             code_size: RefCell::new(None),
             selector,
             calldata_data,
@@ -5487,6 +5768,7 @@ impl<'a> Binary<'a> {
             scratch_len: None,
             parameters: None,
             return_values,
+            gas_meter_blocks: None,
         }
     }
 
@@ -5494,10 +5776,13 @@ impl<'a> Binary<'a> {
     pub fn set_early_value_aborts(&mut self, contract: &ast::Contract, ns: &ast::Namespace) {
         // if there is no payable function, fallback or receive then abort all value transfers at the top
         // note that receive() is always payable so this just checkes for presence.
-        self.function_abort_value_transfers = !contract.functions.iter().any(|function_no| {
+        let is_payable = |function_no: &usize| {
             let f = &ns.functions[*function_no];
             !f.is_constructor() && f.is_payable()
-        });
+        };
+
+        // Inherited functions are dispatched too, so a payable function in a base contract counts.
+        self.function_abort_value_transfers = !contract.all_functions.keys().any(is_payable);
 
         self.constructor_abort_value_transfers = !contract.functions.iter().any(|function_no| {
             let f = &ns.functions[*function_no];
@@ -5505,6 +5790,83 @@ impl<'a> Binary<'a> {
         });
     }
 
+    /// The public functions which are payable, although all value transfers are aborted before
+    /// they are dispatched. `set_early_value_aborts()` should prevent this; should it happen
+    /// anyway, any value sent to these functions is rejected.
+    pub fn payable_under_value_abort(&self, contract: &ast::Contract) -> Vec<usize> {
+        // on ewasm, the functions are dispatched by the runtime code
+        let dispatcher = self.runtime.as_deref().unwrap_or(self);
+
+        if !dispatcher.function_abort_value_transfers {
+            return Vec::new();
+        }
+
+        contract
+            .cfg
+            .iter()
+            .filter(|cfg| {
+                cfg.public
+                    && !cfg.nonpayable
+                    && matches!(
+                        cfg.ty,
+                        pt::FunctionTy::Function
+                            | pt::FunctionTy::Fallback
+                            | pt::FunctionTy::Receive
+                    )
+            })
+            .filter_map(|cfg| cfg.function_no)
+            .collect()
+    }
+
+    /// Does the dispatch case for this function have to reject value transfers itself
+    pub fn function_rejects_value(&self, cfg: &ControlFlowGraph) -> bool {
+        rejects_value_in_dispatch(self.function_abort_value_transfers, cfg.nonpayable)
+    }
+
+    /// The code of another contract, for creating it or for `type(C).creationCode`. The code
+    /// built by `build_all()` is reused if there is any; otherwise the contract is built with
+    /// the same options as this binary, so the result is the same either way.
+    pub fn contract_code(&self, contract_no: usize, runtime: bool, ns: &ast::Namespace) -> Vec<u8> {
+        let contract = &ns.contracts[contract_no];
+
+        if !runtime && !contract.code.is_empty() {
+            return contract.code.clone();
+        }
+
+        let target_bin = Binary::build(self.context, contract, ns, "", &self.options);
+
+        if runtime && target_bin.runtime.is_some() {
+            target_bin
+                .runtime
+                .unwrap()
+                .code(Generate::Linked)
+                .expect("compile should succeeed")
+        } else {
+            target_bin
+                .code(Generate::Linked)
+                .expect("compile should succeeed")
+        }
+    }
+
+    /// The table which maps the block ids passed to `meter_gas` back to the source, as json.
+    /// This is only set when the binary was built with `meter_gas`.
+    pub fn gas_meter_table(&self, ns: &ast::Namespace) -> Option<String> {
+        self.gas_meter_blocks.as_ref().map(|blocks| {
+            let blocks: Vec<GasMeterBlock> = blocks
+                .iter()
+                .enumerate()
+                .map(|(id, (function, block, loc))| GasMeterBlock {
+                    id,
+                    function: function.to_owned(),
+                    block: block.to_owned(),
+                    location: ns.files[loc.0].loc_to_string(loc),
+                })
+                .collect();
+
+            serde_json::to_string_pretty(&blocks).unwrap()
+        })
+    }
+
     /// llvm value type, as in chain currency (usually 128 bits int)
     fn value_type(&self, ns: &ast::Namespace) -> IntType<'a> {
         self.context
@@ -5542,6 +5904,20 @@ impl<'a> Binary<'a> {
         )
     }
 
+    /// The ABI encoded revert data for `Panic(uint256)` with the given code, as Solidity
+    /// produces for failed asserts, overflows and out of bounds accesses
+    fn panic_data(&self, code: u8) -> (PointerValue<'a>, IntValue<'a>) {
+        // 0x4e487b71 is the selector of Panic(uint256)
+        let mut data = vec![0x4e, 0x48, 0x7b, 0x71];
+        data.resize(35, 0);
+        data.push(code);
+
+        (
+            self.emit_global_string(&format!("panic_{:#04x}", code), &data, true),
+            self.context.i32_type().const_int(data.len() as u64, false),
+        )
+    }
+
     /// Wrapper for alloca. Ensures that the alloca is done on the first basic block.
     /// If alloca is not on the first basic block, llvm will get to llvm_unreachable
     /// for the BPF target.
@@ -6236,6 +6612,21 @@ impl<'a> Binary<'a> {
         }
     }
 
+    /// Pointer to and length of the calldata we were called with, so it can be passed on
+    /// as a payload without copying it into a new vector first
+    fn calldata_payload(&self) -> (PointerValue<'a>, IntValue<'a>) {
+        let data = self
+            .builder
+            .build_load(self.calldata_data.as_pointer_value(), "calldata_data")
+            .into_pointer_value();
+        let len = self
+            .builder
+            .build_load(self.calldata_len.as_pointer_value(), "calldata_len")
+            .into_int_value();
+
+        (data, len)
+    }
+
     /// Dereference an array
     fn array_subscript(
         &self,
@@ -6311,6 +6702,67 @@ static KECCAK256_IR: &[u8] = include_bytes!("../../stdlib/wasm/keccak256.bc");
 static RIPEMD160_IR: &[u8] = include_bytes!("../../stdlib/wasm/ripemd160.bc");
 static SUBSTRATE_IR: &[u8] = include_bytes!("../../stdlib/wasm/substrate.bc");
 
+/// Decide which functions get the alwaysinline hint: internal functions which are called once,
+/// or which are tiny. A recursive function cannot be inlined into itself, so it never gets it.
+fn inline_hints(contract: &ast::Contract) -> Vec<bool> {
+    // the internal functions each function calls directly
+    let callees: Vec<Vec<usize>> = contract
+        .cfg
+        .iter()
+        .map(|cfg| {
+            cfg.blocks
+                .iter()
+                .flat_map(|block| block.instr.iter())
+                .filter_map(|instr| match instr {
+                    Instr::Call {
+                        call: InternalCallTy::Static(cfg_no),
+                        ..
+                    } => Some(*cfg_no),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut calls = vec![0; contract.cfg.len()];
+
+    for cfg_no in callees.iter().flatten() {
+        calls[*cfg_no] += 1;
+    }
+
+    contract
+        .cfg
+        .iter()
+        .enumerate()
+        .map(|(cfg_no, cfg)| {
+            !cfg.public
+                && (calls[cfg_no] == 1
+                    || (cfg.blocks.len() == 1
+                        && cfg.blocks[0].instr.len() <= INLINE_MAX_INSTRUCTIONS))
+                && !is_recursive(cfg_no, &callees)
+        })
+        .collect()
+}
+
+/// Can the function call itself, directly or through other functions
+fn is_recursive(cfg_no: usize, callees: &[Vec<usize>]) -> bool {
+    let mut visited = vec![false; callees.len()];
+    let mut todo = callees[cfg_no].clone();
+
+    while let Some(callee) = todo.pop() {
+        if callee == cfg_no {
+            return true;
+        }
+
+        if !visited[callee] {
+            visited[callee] = true;
+            todo.extend_from_slice(&callees[callee]);
+        }
+    }
+
+    false
+}
+
 /// Return the stdlib as parsed llvm module. The solidity standard library is hardcoded into
 /// the solang library
 fn load_stdlib<'a>(context: &'a Context, target: &Target) -> Module<'a> {
@@ -6418,3 +6870,25 @@ impl Target {
         }
     }
 }
+
+/// A non-payable function must reject value transfers, unless all value transfers are already
+/// aborted before dispatch because no function is payable; then the check would be dead code.
+fn rejects_value_in_dispatch(function_abort_value_transfers: bool, nonpayable: bool) -> bool {
+    !function_abort_value_transfers && nonpayable
+}
+
+#[cfg(test)]
+mod test {
+    use super::rejects_value_in_dispatch;
+
+    #[test]
+    fn value_rejection() {
+        // value is aborted before dispatch, so no function needs to check
+        assert!(!rejects_value_in_dispatch(true, true));
+        assert!(!rejects_value_in_dispatch(true, false));
+
+        // some function is payable, so the non-payable ones have to check
+        assert!(rejects_value_in_dispatch(false, true));
+        assert!(!rejects_value_in_dispatch(false, false));
+    }
+}
@@ -1,4 +1,5 @@
 use crate::codegen::cfg::HashTy;
+use crate::codegen::Options;
 use crate::parser::pt;
 use crate::sema::ast;
 use std::collections::HashMap;
@@ -10,7 +11,6 @@ use inkwell::types::IntType;
 use inkwell::values::{BasicValueEnum, FunctionValue, IntValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
-use inkwell::OptimizationLevel;
 
 use super::ethabiencoder;
 use super::{Binary, TargetRuntime, Variable};
@@ -25,21 +25,15 @@ impl SabreTarget {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        opt: &Options,
     ) -> Binary<'a> {
         let mut b = SabreTarget {
-            abi: ethabiencoder::EthAbiDecoder { bswap: false },
+            abi: ethabiencoder::EthAbiDecoder {
+                bswap: false,
+                strict: false,
+            },
         };
-        let mut c = Binary::new(
-            context,
-            ns.target,
-            &contract.name,
-            filename,
-            opt,
-            math_overflow_check,
-            None,
-        );
+        let mut c = Binary::new(context, ns.target, &contract.name, filename, opt, None);
 
         // externals
         b.declare_externals(&mut c);
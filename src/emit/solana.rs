@@ -1,4 +1,5 @@
 use crate::codegen::cfg::HashTy;
+use crate::codegen::Options;
 use crate::parser::pt;
 use crate::sema::ast;
 use crate::Target;
@@ -37,23 +38,17 @@ impl SolanaTarget {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        opt: &Options,
     ) -> Binary<'a> {
         let mut target = SolanaTarget {
-            abi: ethabiencoder::EthAbiDecoder { bswap: true },
+            abi: ethabiencoder::EthAbiDecoder {
+                bswap: true,
+                strict: false,
+            },
             magic: contract.selector(),
         };
 
-        let mut binary = Binary::new(
-            context,
-            Target::Solana,
-            &contract.name,
-            filename,
-            opt,
-            math_overflow_check,
-            None,
-        );
+        let mut binary = Binary::new(context, Target::Solana, &contract.name, filename, opt, None);
 
         binary
             .return_values
@@ -116,7 +111,10 @@ impl SolanaTarget {
         math_overflow_check: bool,
     ) -> Binary<'a> {
         let mut target = SolanaTarget {
-            abi: ethabiencoder::EthAbiDecoder { bswap: true },
+            abi: ethabiencoder::EthAbiDecoder {
+                bswap: true,
+                strict: false,
+            },
             magic: 0,
         };
 
@@ -125,8 +123,11 @@ impl SolanaTarget {
             Target::Solana,
             "bundle",
             filename,
-            opt,
-            math_overflow_check,
+            &Options {
+                opt_level: opt,
+                math_overflow_check,
+                ..Default::default()
+            },
             None,
         );
 
@@ -1,4 +1,5 @@
 use crate::codegen::cfg::HashTy;
+use crate::codegen::Options;
 use crate::parser::pt;
 use crate::sema::ast;
 use inkwell::context::Context;
@@ -7,7 +8,6 @@ use inkwell::types::{BasicType, IntType};
 use inkwell::values::{BasicValueEnum, CallableValue, FunctionValue, IntValue, PointerValue};
 use inkwell::AddressSpace;
 use inkwell::IntPredicate;
-use inkwell::OptimizationLevel;
 use num_traits::ToPrimitive;
 use std::collections::HashMap;
 use std::convert::TryFrom;
@@ -27,18 +27,9 @@ impl SubstrateTarget {
         contract: &'a ast::Contract,
         ns: &'a ast::Namespace,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        opt: &Options,
     ) -> Binary<'a> {
-        let mut binary = Binary::new(
-            context,
-            ns.target,
-            &contract.name,
-            filename,
-            opt,
-            math_overflow_check,
-            None,
-        );
+        let mut binary = Binary::new(context, ns.target, &contract.name, filename, opt, None);
 
         binary.set_early_value_aborts(contract, ns);
 
@@ -567,7 +558,7 @@ impl SubstrateTarget {
             function,
             &binary.functions,
             None,
-            |func| !binary.function_abort_value_transfers && func.nonpayable,
+            |func| binary.function_rejects_value(func),
         );
     }
 
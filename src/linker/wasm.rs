@@ -39,6 +39,8 @@ pub fn link(input: &[u8], name: &str, target: Target) -> Vec<u8> {
             command_line.push(CString::new("main").unwrap());
         }
         Target::Lachain => {
+            command_line.push(CString::new("--export").unwrap());
+            command_line.push(CString::new("deploy").unwrap());
             command_line.push(CString::new("--export").unwrap());
             command_line.push(CString::new("start").unwrap());
         }
@@ -402,6 +402,10 @@ pub struct Namespace {
     pub var_constants: HashMap<pt::Loc, Expression>,
     /// Overrides for hover in the language server
     pub hover_overrides: HashMap<pt::Loc, String>,
+    /// Nesting depth of the expression currently being resolved
+    pub expression_depth: usize,
+    /// Levels of the expression currently being resolved, including the links of flat chains
+    pub expression_levels: usize,
 }
 
 pub struct Layout {
@@ -1325,6 +1329,9 @@ pub enum Builtin {
     Gasprice,
     Origin,
     BlockHash,
+    BlobHash,
+    BlobBaseFee,
+    BaseFee,
     Random,
     MinimumBalance,
     TombstoneDeposit,
@@ -1333,11 +1340,14 @@ pub enum Builtin {
     AbiEncodePacked,
     AbiEncodeWithSelector,
     AbiEncodeWithSignature,
+    AbiEncodeCall,
     MulMod,
     AddMod,
     ExternalFunctionAddress,
     FunctionSelector,
     SignatureVerify,
+    ExternalCode,
+    ExternalCodeSize,
 }
 
 #[derive(PartialEq, Clone, Debug)]
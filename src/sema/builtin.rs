@@ -1,6 +1,7 @@
 use super::ast::{Builtin, Diagnostic, Expression, Namespace, Type};
 use super::eval::eval_const_number;
 use super::expression::{cast, expression};
+use super::statements;
 use super::symtable::Symtable;
 use crate::parser::pt;
 use crate::Target;
@@ -20,7 +21,7 @@ pub struct Prototype {
 }
 
 // A list of all Solidity builtins functions
-static BUILTIN_FUNCTIONS: [Prototype; 25] = [
+static BUILTIN_FUNCTIONS: [Prototype; 27] = [
     Prototype {
         builtin: Builtin::Assert,
         namespace: None,
@@ -161,6 +162,16 @@ static BUILTIN_FUNCTIONS: [Prototype; 25] = [
         doc: "Returns the block hash for given block number",
         constant: false,
     },
+    Prototype {
+        builtin: Builtin::BlobHash,
+        namespace: None,
+        name: "blobhash",
+        args: &[Type::Uint(256)],
+        ret: &[Type::Bytes(32)],
+        target: Some(Target::Lachain),
+        doc: "Returns the versioned hash of the given blob. Always zero on Lachain",
+        constant: false,
+    },
     Prototype {
         builtin: Builtin::Random,
         namespace: None,
@@ -225,6 +236,17 @@ static BUILTIN_FUNCTIONS: [Prototype; 25] = [
         // it should be allowed in constant context, but we don't supported that yet
         constant: false,
     },
+    Prototype {
+        builtin: Builtin::AbiEncodeCall,
+        namespace: Some("abi"),
+        name: "encodeCall",
+        args: &[],
+        ret: &[],
+        target: None,
+        doc: "Abi encode given arguments for a call to the given function",
+        // it should be allowed in constant context, but we don't supported that yet
+        constant: false,
+    },
     Prototype {
         builtin: Builtin::Gasprice,
         namespace: Some("tx"),
@@ -280,7 +302,7 @@ static BUILTIN_FUNCTIONS: [Prototype; 25] = [
 ];
 
 // A list of all Solidity builtins variables
-static BUILTIN_VARIABLE: [Prototype; 14] = [
+static BUILTIN_VARIABLE: [Prototype; 16] = [
     Prototype {
         builtin: Builtin::BlockCoinbase,
         namespace: Some("block"),
@@ -341,6 +363,26 @@ static BUILTIN_VARIABLE: [Prototype; 14] = [
         doc: "Current chain id",
         constant: false,
     },
+    Prototype {
+        builtin: Builtin::BaseFee,
+        namespace: Some("block"),
+        name: "basefee",
+        args: &[],
+        ret: &[Type::Uint(256)],
+        target: Some(Target::Lachain),
+        doc: "Current block base fee",
+        constant: false,
+    },
+    Prototype {
+        builtin: Builtin::BlobBaseFee,
+        namespace: Some("block"),
+        name: "blobbasefee",
+        args: &[],
+        ret: &[Type::Uint(256)],
+        target: Some(Target::Lachain),
+        doc: "Current blob base fee. Always zero on Lachain",
+        constant: false,
+    },
     Prototype {
         builtin: Builtin::TombstoneDeposit,
         namespace: Some("block"),
@@ -462,6 +504,27 @@ pub fn builtin_var(
                     ),
                 ));
             }
+            if p.builtin == Builtin::BlobBaseFee {
+                blob_warning(loc, "block.blobbasefee", ns, diagnostics);
+            }
+            if p.builtin == Builtin::BaseFee {
+                diagnostics.push(Diagnostic::error(
+                    *loc,
+                    String::from(
+                        "‘block.basefee’ is not available, since the Lachain host does not provide the base fee",
+                    ),
+                ));
+            }
+            // Lachain follows Solidity and provides the chain id, block number and timestamp
+            // as a uint256
+            if ns.target == Target::Lachain
+                && matches!(
+                    p.builtin,
+                    Builtin::ChainId | Builtin::BlockNumber | Builtin::Timestamp
+                )
+            {
+                return Some((p.builtin, Type::Uint(256)));
+            }
             return Some((p.builtin, p.ret[0].clone()));
         }
     }
@@ -469,6 +532,23 @@ pub fn builtin_var(
     None
 }
 
+/// Blobs do not exist on Lachain, so the blob builtins always evaluate to zero. Warn about
+/// this once rather than at every use.
+fn blob_warning(loc: &pt::Loc, name: &str, ns: &Namespace, diagnostics: &mut Vec<Diagnostic>) {
+    let message = format!(
+        "‘{}’ not supported on target {}, it always evaluates to zero",
+        name, ns.target
+    );
+
+    if !diagnostics
+        .iter()
+        .chain(ns.diagnostics.iter())
+        .any(|d| d.message == message)
+    {
+        diagnostics.push(Diagnostic::warning(*loc, message));
+    }
+}
+
 /// Is name reserved for builtins
 pub fn is_reserved(fname: &str) -> bool {
     if fname == "type" || fname == "super" {
@@ -592,12 +672,18 @@ pub fn resolve_call(
                 }
             }
 
-            return Ok(Expression::Builtin(
-                *loc,
-                func.ret.to_vec(),
-                func.builtin,
-                cast_args,
-            ));
+            if func.builtin == Builtin::BlobHash {
+                blob_warning(loc, "blobhash", ns, diagnostics);
+            }
+
+            // Lachain follows Solidity and provides the gas left as a uint256
+            let ret = if ns.target == Target::Lachain && func.builtin == Builtin::Gasleft {
+                vec![Type::Uint(256)]
+            } else {
+                func.ret.to_vec()
+            };
+
+            return Ok(Expression::Builtin(*loc, ret, func.builtin, cast_args));
         }
     }
 
@@ -647,6 +733,130 @@ pub fn resolve_method_call(
         );
     }
 
+    if name == "encodeCall" {
+        if args.len() != 2 {
+            diagnostics.push(Diagnostic::error(
+                *loc,
+                format!("function expects {} arguments, {} provided", 2, args.len()),
+            ));
+
+            return Err(());
+        }
+
+        // first argument is the function being called
+        let function = expression(
+            &args[0],
+            file_no,
+            contract_no,
+            function_no,
+            ns,
+            symtable,
+            false,
+            unchecked,
+            diagnostics,
+            None,
+        )?;
+
+        let call_function_no = match function {
+            Expression::InternalFunction { function_no, .. }
+            | Expression::ExternalFunction { function_no, .. } => function_no,
+            _ => {
+                diagnostics.push(Diagnostic::error(
+                    args[0].loc(),
+                    "first argument of abi.encodeCall must be a function".to_string(),
+                ));
+
+                return Err(());
+            }
+        };
+
+        if !ns.functions[call_function_no].is_public() {
+            diagnostics.push(Diagnostic::error(
+                args[0].loc(),
+                format!(
+                    "function ‘{}’ is not public or external",
+                    ns.functions[call_function_no].name
+                ),
+            ));
+
+            return Err(());
+        }
+
+        // second argument is the tuple of arguments, checked against the function signature
+        let call_args = statements::parameter_list_to_expr_list(&args[1], diagnostics)?;
+        let params = ns.functions[call_function_no].params.clone();
+
+        if call_args.len() != params.len() {
+            diagnostics.push(Diagnostic::error(
+                args[1].loc(),
+                format!(
+                    "function expects {} arguments, {} provided",
+                    params.len(),
+                    call_args.len()
+                ),
+            ));
+
+            return Err(());
+        }
+
+        let mut resolved_args = vec![Expression::BytesLiteral(
+            args[0].loc(),
+            Type::Bytes(4),
+            ns.functions[call_function_no]
+                .selector()
+                .to_be_bytes()
+                .to_vec(),
+        )];
+
+        for (i, (arg, param)) in call_args.into_iter().zip(params.iter()).enumerate() {
+            let expr = expression(
+                arg,
+                file_no,
+                contract_no,
+                function_no,
+                ns,
+                symtable,
+                false,
+                unchecked,
+                diagnostics,
+                Some(&param.ty),
+            )?;
+
+            let ty = expr.ty();
+            let mut cast_diagnostics = Vec::new();
+
+            match cast(&arg.loc(), expr, &param.ty, true, ns, &mut cast_diagnostics) {
+                Ok(expr) => {
+                    diagnostics.extend(cast_diagnostics);
+
+                    resolved_args.push(expr);
+                }
+                Err(()) => {
+                    diagnostics.push(Diagnostic::error(
+                        arg.loc(),
+                        format!(
+                            "argument {} of abi.encodeCall is ‘{}’, but function ‘{}’ expects ‘{}’",
+                            i + 1,
+                            ty.to_string(ns),
+                            ns.functions[call_function_no].name,
+                            param.ty.to_string(ns)
+                        ),
+                    ));
+
+                    return Err(());
+                }
+            }
+        }
+
+        // the first argument is the selector, like abi.encodeWithSelector()
+        return Ok(Expression::Builtin(
+            *loc,
+            vec![Type::DynamicBytes],
+            Builtin::AbiEncodeCall,
+            resolved_args,
+        ));
+    }
+
     let builtin = match name {
         "decode" => Builtin::AbiDecode,
         "encode" => Builtin::AbiEncode,
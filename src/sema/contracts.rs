@@ -1,5 +1,4 @@
 use crate::parser::pt;
-use inkwell::OptimizationLevel;
 use num_bigint::BigInt;
 use num_traits::Zero;
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -13,6 +12,7 @@ use super::functions;
 use super::statements;
 use super::symtable::Symtable;
 use super::variables;
+use crate::codegen::Options;
 use crate::emit;
 use crate::sema::unused_variable::emit_warning_local_variable;
 
@@ -47,10 +47,9 @@ impl ast::Contract {
         ns: &'a ast::Namespace,
         context: &'a inkwell::context::Context,
         filename: &'a str,
-        opt: OptimizationLevel,
-        math_overflow_check: bool,
+        opt: &Options,
     ) -> emit::Binary {
-        emit::Binary::build(context, self, ns, filename, opt, math_overflow_check)
+        emit::Binary::build(context, self, ns, filename, opt)
     }
 
     /// Selector for this contract. This is used by Solana contract bundle
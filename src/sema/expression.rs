@@ -1260,6 +1260,108 @@ pub fn compatible_mutability(left: &Mutability, right: &Mutability) -> bool {
     )
 }
 
+/// Maximum nesting depth of expressions. Resolving, generating code for and emitting expressions
+/// all recurse, so deeper expressions are rejected rather than overflowing the compiler's stack.
+/// A flat chain like `a + b + c` counts as a single level, see `continues_chain()`.
+pub const MAX_EXPRESSION_DEPTH: usize = 256;
+
+/// Maximum number of levels an expression may have when the links of flat chains are counted
+/// too. A chain is still a left-deep tree, so it is walked recursively like any other nesting.
+pub const MAX_EXPRESSION_LEVELS: usize = 1024;
+
+/// While an expression is being resolved, this holds the namespace with the expression depth
+/// and levels incremented. They are decremented again when it is dropped, whichever way
+/// resolving returns.
+struct ExpressionDepth<'a> {
+    ns: &'a mut Namespace,
+    nested: bool,
+}
+
+impl<'a> ExpressionDepth<'a> {
+    fn enter(
+        ns: &'a mut Namespace,
+        expr: &pt::Expression,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<Self, ()> {
+        let nested = !continues_chain(expr);
+
+        if nested && ns.expression_depth >= MAX_EXPRESSION_DEPTH {
+            diagnostics.push(Diagnostic::error(
+                expr.loc(),
+                format!(
+                    "expression is nested too deeply; maximum depth is {}",
+                    MAX_EXPRESSION_DEPTH
+                ),
+            ));
+            return Err(());
+        }
+
+        if ns.expression_levels >= MAX_EXPRESSION_LEVELS {
+            diagnostics.push(Diagnostic::error(
+                expr.loc(),
+                format!(
+                    "expression has too many chained or nested operators; maximum is {}",
+                    MAX_EXPRESSION_LEVELS
+                ),
+            ));
+            return Err(());
+        }
+
+        if nested {
+            ns.expression_depth += 1;
+        }
+
+        ns.expression_levels += 1;
+
+        Ok(ExpressionDepth { ns, nested })
+    }
+}
+
+impl<'a> Drop for ExpressionDepth<'a> {
+    fn drop(&mut self) {
+        if self.nested {
+            self.ns.expression_depth -= 1;
+        }
+
+        self.ns.expression_levels -= 1;
+    }
+}
+
+/// Return the left operand of a binary expression
+fn binary_left_operand(expr: &pt::Expression) -> Option<&pt::Expression> {
+    match expr {
+        pt::Expression::Power(_, left, _)
+        | pt::Expression::Multiply(_, left, _)
+        | pt::Expression::Divide(_, left, _)
+        | pt::Expression::Modulo(_, left, _)
+        | pt::Expression::Add(_, left, _)
+        | pt::Expression::Subtract(_, left, _)
+        | pt::Expression::ShiftLeft(_, left, _)
+        | pt::Expression::ShiftRight(_, left, _)
+        | pt::Expression::BitwiseAnd(_, left, _)
+        | pt::Expression::BitwiseXor(_, left, _)
+        | pt::Expression::BitwiseOr(_, left, _)
+        | pt::Expression::Less(_, left, _)
+        | pt::Expression::More(_, left, _)
+        | pt::Expression::LessEqual(_, left, _)
+        | pt::Expression::MoreEqual(_, left, _)
+        | pt::Expression::Equal(_, left, _)
+        | pt::Expression::NotEqual(_, left, _)
+        | pt::Expression::And(_, left, _)
+        | pt::Expression::Or(_, left, _) => Some(left),
+        _ => None,
+    }
+}
+
+/// Binary operators are left associative, so `a + b + c` is parsed as `(a + b) + c`. A binary
+/// expression whose left operand is a binary expression continues such a chain, rather than
+/// nesting, so it adds to the expression levels but not to its depth.
+fn continues_chain(expr: &pt::Expression) -> bool {
+    binary_left_operand(expr)
+        .and_then(binary_left_operand)
+        .is_some()
+}
+
 /// Resolve a parsed expression into an AST expression. The resolve_to argument is a hint to what
 /// type the result should be.
 pub fn expression(
@@ -1273,6 +1375,34 @@ pub fn expression(
     unchecked: bool,
     diagnostics: &mut Vec<Diagnostic>,
     resolve_to: Option<&Type>,
+) -> Result<Expression, ()> {
+    let mut depth = ExpressionDepth::enter(ns, expr, diagnostics)?;
+
+    resolve_expression(
+        expr,
+        file_no,
+        contract_no,
+        function_no,
+        depth.ns,
+        symtable,
+        is_constant,
+        unchecked,
+        diagnostics,
+        resolve_to,
+    )
+}
+
+fn resolve_expression(
+    expr: &pt::Expression,
+    file_no: usize,
+    contract_no: Option<usize>,
+    function_no: Option<usize>,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    is_constant: bool,
+    unchecked: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+    resolve_to: Option<&Type>,
 ) -> Result<Expression, ()> {
     match expr {
         pt::Expression::ArrayLiteral(loc, exprs) => {
@@ -4204,6 +4334,21 @@ fn addition(
         return Ok(Expression::BytesLiteral(*loc, Type::Bytes(length as u8), c));
     }
 
+    // On Lachain, strings and bytes in storage are loaded so they can be concatenated
+    if ns.target == Target::Lachain {
+        let ty = left.ty();
+
+        if matches!(ty.deref_any(), Type::String | Type::DynamicBytes) {
+            left = cast(&l.loc(), left, ty.deref_any(), true, ns, diagnostics)?;
+        }
+
+        let ty = right.ty();
+
+        if matches!(ty.deref_any(), Type::String | Type::DynamicBytes) {
+            right = cast(&r.loc(), right, ty.deref_any(), true, ns, diagnostics)?;
+        }
+    }
+
     let left_type = left.ty();
     let right_type = right.ty();
 
@@ -5135,6 +5280,24 @@ fn member_access(
                     vec![expr],
                 ));
             }
+
+            if id.name == "code" {
+                if ns.target != crate::Target::Lachain {
+                    diagnostics.push(Diagnostic::error(
+                        *loc,
+                        format!("‘address.code’ not supported on target {}", ns.target),
+                    ));
+                    return Err(());
+                }
+
+                used_variable(ns, &expr, symtable);
+                return Ok(Expression::Builtin(
+                    *loc,
+                    vec![Type::DynamicBytes],
+                    Builtin::ExternalCode,
+                    vec![expr],
+                ));
+            }
         }
         Type::Contract(ref_contract_no) => {
             let mut name_matches = 0;
@@ -6079,6 +6242,108 @@ fn named_struct_literal(
     }
 }
 
+/// Resolve bytes.concat(), which concatenates bytes and fixed length bytes into bytes
+fn bytes_concat(
+    loc: &pt::Loc,
+    args: &[pt::Expression],
+    file_no: usize,
+    contract_no: Option<usize>,
+    function_no: Option<usize>,
+    unchecked: bool,
+    ns: &mut Namespace,
+    symtable: &mut Symtable,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Expression, ()> {
+    let mut res = StringLocation::CompileTime(Vec::new());
+
+    for arg in args {
+        let expr = expression(
+            arg,
+            file_no,
+            contract_no,
+            function_no,
+            ns,
+            symtable,
+            false,
+            unchecked,
+            diagnostics,
+            Some(&Type::DynamicBytes),
+        )?;
+
+        let right = if let Expression::BytesLiteral(_, _, v) = expr {
+            StringLocation::CompileTime(v)
+        } else {
+            let ty = expr.ty();
+
+            match ty.deref_any() {
+                Type::DynamicBytes => StringLocation::RunTime(Box::new(cast(
+                    &arg.loc(),
+                    expr,
+                    &Type::DynamicBytes,
+                    true,
+                    ns,
+                    diagnostics,
+                )?)),
+                Type::Bytes(_) => StringLocation::RunTime(Box::new(cast(
+                    &arg.loc(),
+                    expr,
+                    &Type::DynamicBytes,
+                    false,
+                    ns,
+                    diagnostics,
+                )?)),
+                _ => {
+                    diagnostics.push(Diagnostic::error(
+                        arg.loc(),
+                        format!(
+                            "‘bytes.concat’ expects bytes or fixed length bytes, not ‘{}’",
+                            ty.to_string(ns)
+                        ),
+                    ));
+                    return Err(());
+                }
+            }
+        };
+
+        res = match (res, right) {
+            (StringLocation::CompileTime(mut l), StringLocation::CompileTime(r)) => {
+                l.extend_from_slice(&r);
+                StringLocation::CompileTime(l)
+            }
+            (StringLocation::CompileTime(l), right) if l.is_empty() => right,
+            (left, right) => StringLocation::RunTime(Box::new(Expression::StringConcat(
+                *loc,
+                Type::DynamicBytes,
+                left,
+                right,
+            ))),
+        };
+    }
+
+    Ok(match res {
+        StringLocation::RunTime(expr) => match *expr {
+            expr @ Expression::StringConcat(..) => expr,
+            // a lone argument is copied, since the result must not share memory with it
+            expr => Expression::StringConcat(
+                *loc,
+                Type::DynamicBytes,
+                StringLocation::CompileTime(Vec::new()),
+                StringLocation::RunTime(Box::new(expr)),
+            ),
+        },
+        StringLocation::CompileTime(v) => Expression::AllocDynamicArray(
+            *loc,
+            Type::DynamicBytes,
+            Box::new(Expression::NumberLiteral(
+                *loc,
+                Type::Uint(32),
+                BigInt::from(v.len()),
+            )),
+            Some(v),
+        ),
+    })
+}
+
 /// Resolve a method call with positional arguments
 fn method_call_pos_args(
     loc: &pt::Loc,
@@ -6095,6 +6360,30 @@ fn method_call_pos_args(
     symtable: &mut Symtable,
     diagnostics: &mut Vec<Diagnostic>,
 ) -> Result<Expression, ()> {
+    if let pt::Expression::Type(_, pt::Type::DynamicBytes) = var {
+        if func.name == "concat" {
+            if let Some(loc) = call_args_loc {
+                diagnostics.push(Diagnostic::error(
+                    loc,
+                    "call arguments not allowed on builtins".to_string(),
+                ));
+                return Err(());
+            }
+
+            return bytes_concat(
+                loc,
+                args,
+                file_no,
+                contract_no,
+                arg_function_no,
+                unchecked,
+                ns,
+                symtable,
+                diagnostics,
+            );
+        }
+    }
+
     if let pt::Expression::Variable(namespace) = var {
         if builtin::is_builtin_call(Some(&namespace.name), &func.name, ns) {
             if let Some(loc) = call_args_loc {
@@ -6733,6 +7022,18 @@ fn method_call_pos_args(
 
             let value = cast(&args[0].loc(), expr, &Type::Value, true, ns, diagnostics)?;
 
+            // the Lachain transfer host function has no gas argument, so the receiver gets
+            // all the remaining gas rather than a stipend
+            if ns.target == Target::Lachain {
+                diagnostics.push(Diagnostic::warning(
+                    *loc,
+                    format!(
+                        "‘{}’ on Lachain forwards all remaining gas, not the 2300 gas stipend",
+                        func.name
+                    ),
+                ));
+            }
+
             return if func.name == "transfer" {
                 Ok(Expression::Builtin(
                     *loc,
@@ -328,6 +328,8 @@ impl ast::Namespace {
             next_id: 0,
             var_constants: HashMap::new(),
             hover_overrides: HashMap::new(),
+            expression_depth: 0,
+            expression_levels: 0,
         }
     }
 
@@ -1215,6 +1217,13 @@ impl ast::Namespace {
                         ast::Type::Address(true)
                     }
                 }
+                pt::Type::Bytes(n) if *n > 32 => {
+                    diagnostics.push(ast::Diagnostic::decl_error(
+                        *loc,
+                        format!("type ‘bytes{}’ not supported, maximum is ‘bytes32’", n),
+                    ));
+                    return Err(());
+                }
                 _ => ast::Type::from(ty),
             };
 
@@ -249,6 +249,10 @@ fn read_expression(expr: &Expression, state: &mut StateCheck) -> bool {
         | Expression::Builtin(loc, _, Builtin::BlockCoinbase, _)
         | Expression::Builtin(loc, _, Builtin::BlockDifficulty, _)
         | Expression::Builtin(loc, _, Builtin::BlockHash, _)
+        | Expression::Builtin(loc, _, Builtin::BlobHash, _)
+        | Expression::Builtin(loc, _, Builtin::BlobBaseFee, _)
+        | Expression::Builtin(loc, _, Builtin::BaseFee, _)
+        | Expression::Builtin(loc, _, Builtin::ExternalCode, _)
         | Expression::Builtin(loc, _, Builtin::Sender, _)
         | Expression::Builtin(loc, _, Builtin::Origin, _)
         | Expression::Builtin(loc, _, Builtin::Gasleft, _)
@@ -8,6 +8,7 @@ use super::symtable::{LoopScopes, Symtable};
 use crate::parser::pt;
 use crate::sema::symtable::VariableUsage;
 use crate::sema::unused_variable::{assigned_variable, check_function_call, used_variable};
+use crate::Target;
 use std::collections::HashMap;
 
 pub fn resolve_function_body(
@@ -924,16 +925,99 @@ fn statement(
 
             Ok(true)
         }
-        pt::Statement::Assembly { loc, .. } => {
-            ns.diagnostics.push(Diagnostic::error(
+        pt::Statement::Assembly { loc, assembly } => {
+            // On Lachain, a block of nothing but invalid() is an assert failure, which halts
+            // without return data
+            if ns.target == Target::Lachain
+                && !assembly.is_empty()
+                && assembly.iter().all(is_assembly_invalid)
+            {
+                res.push(Statement::Expression(
+                    *loc,
+                    false,
+                    Expression::Builtin(
+                        *loc,
+                        vec![Type::Void],
+                        Builtin::Assert,
+                        vec![Expression::BoolLiteral(*loc, false)],
+                    ),
+                ));
+
+                return Ok(false);
+            }
+
+            let mut notes = Vec::new();
+
+            for stmt in assembly {
+                match stmt {
+                    pt::AssemblyStatement::Assign(_, left, right)
+                    | pt::AssemblyStatement::LetAssign(_, left, right) => {
+                        assembly_instructions(left, &mut notes);
+                        assembly_instructions(right, &mut notes);
+                    }
+                    pt::AssemblyStatement::Expression(expr) => {
+                        assembly_instructions(expr, &mut notes);
+                    }
+                }
+            }
+
+            ns.diagnostics.push(Diagnostic::error_with_notes(
                 *loc,
                 format!("evm assembly not supported on target {}", ns.target),
+                notes,
             ));
             Err(())
         }
     }
 }
 
+/// Is this assembly statement a call to invalid() without arguments
+fn is_assembly_invalid(stmt: &pt::AssemblyStatement) -> bool {
+    if let pt::AssemblyStatement::Expression(pt::AssemblyExpression::Function(_, func, args)) = stmt
+    {
+        if let pt::AssemblyExpression::Variable(id) = func.as_ref() {
+            return id.name == "invalid" && args.is_empty();
+        }
+    }
+
+    false
+}
+
+/// Add a note for each evm instruction used in an assembly expression, so the user knows
+/// what needs rewriting in Solidity
+fn assembly_instructions(expr: &pt::AssemblyExpression, notes: &mut Vec<Note>) {
+    match expr {
+        pt::AssemblyExpression::Function(_, func, args) => {
+            if let pt::AssemblyExpression::Variable(id) = func.as_ref() {
+                notes.push(Note {
+                    pos: id.loc,
+                    message: format!("instruction ‘{}’ not supported", id.name),
+                });
+            } else {
+                assembly_instructions(func, notes);
+            }
+
+            for arg in args {
+                assembly_instructions(arg, notes);
+            }
+        }
+        pt::AssemblyExpression::Assign(_, left, right)
+        | pt::AssemblyExpression::LetAssign(_, left, right)
+        | pt::AssemblyExpression::Subscript(_, left, right) => {
+            assembly_instructions(left, notes);
+            assembly_instructions(right, notes);
+        }
+        pt::AssemblyExpression::Member(_, expr, _) => {
+            assembly_instructions(expr, notes);
+        }
+        pt::AssemblyExpression::BoolLiteral(..)
+        | pt::AssemblyExpression::NumberLiteral(..)
+        | pt::AssemblyExpression::HexNumberLiteral(..)
+        | pt::AssemblyExpression::StringLiteral(..)
+        | pt::AssemblyExpression::Variable(..) => (),
+    }
+}
+
 /// Resolve emit event
 fn emit_event(
     loc: &pt::Loc,
@@ -425,6 +425,16 @@ fn event_decl(
         valid = false;
     }
 
+    if ns.target == Target::Lachain && indexed_fields > 0 {
+        ns.diagnostics.push(Diagnostic::warning(
+            def.name.loc,
+            format!(
+                "indexed fields of event ‘{}’ are not logged on Lachain, since its log has no topics",
+                def.name.name
+            ),
+        ));
+    }
+
     if valid {
         let doc = resolve_tags(
             def.name.loc.0,
@@ -0,0 +1,1072 @@
+use ethabi::Token;
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use rand::Rng;
+use ripemd160::Ripemd160;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use tiny_keccak::{Hasher, Keccak};
+use wasmi::memory_units::Pages;
+use wasmi::*;
+
+use solang::abi::generate_abi;
+use solang::codegen::{codegen, Options};
+use solang::emit::Generate;
+use solang::file_cache::FileCache;
+use solang::sema::{ast, diagnostics};
+use solang::{compile, Target};
+
+mod lachain_tests;
+
+type Address = [u8; 20];
+
+fn address_new() -> Address {
+    let mut rng = rand::thread_rng();
+
+    let mut a = [0u8; 20];
+
+    rng.fill(&mut a[..]);
+
+    a
+}
+
+struct VirtualMachine {
+    memory: MemoryRef,
+    cur: Address,
+    caller: Address,
+    value: u128,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    returndata: Vec<u8>,
+}
+
+impl VirtualMachine {
+    fn new(address: Address, caller: Address, value: u128) -> Self {
+        VirtualMachine {
+            memory: MemoryInstance::alloc(Pages(2), Some(Pages(2))).unwrap(),
+            input: Vec::new(),
+            output: Vec::new(),
+            returndata: Vec::new(),
+            cur: address,
+            caller,
+            value,
+        }
+    }
+}
+
+struct TestRuntime {
+    abi: ethabi::Contract,
+    contracts: Vec<Vec<u8>>,
+    value: u128,
+    accounts: HashMap<Address, (Vec<u8>, u128)>,
+    store: HashMap<(Address, [u8; 32]), [u8; 32]>,
+    string_store: HashMap<(Address, [u8; 32]), Vec<u8>>,
+    events: Vec<Vec<u8>>,
+    storage_writes: usize,
+    /// the account which started the transaction, unlike the caller this stays the same
+    /// across nested calls
+    origin: Address,
+    vm: VirtualMachine,
+}
+
+#[derive(FromPrimitive)]
+#[allow(non_camel_case_types)]
+pub enum Extern {
+    get_call_size = 1,
+    copy_call_value,
+    load_storage,
+    save_storage,
+    load_storage_string,
+    save_storage_string,
+    get_storage_string_size,
+    set_return,
+    system_halt,
+    create,
+    invoke_contract,
+    invoke_static_contract,
+    invoke_delegate_contract,
+    transfer,
+    copy_return_value,
+    get_return_size,
+    get_msgvalue,
+    get_address,
+    get_sender,
+    get_external_balance,
+    get_external_code_size,
+    copy_external_code,
+    get_gas_left,
+    get_block_number,
+    get_block_timestamp,
+    get_chain_id,
+    get_tx_origin,
+    crypto_keccak256,
+    crypto_sha256,
+    crypto_ripemd160,
+    crypto_recover,
+    write_log,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HostCodeFinish {}
+
+impl HostError for HostCodeFinish {}
+
+impl fmt::Display for HostCodeFinish {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "finish")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HostCodeRevert {}
+
+impl fmt::Display for HostCodeRevert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "revert")
+    }
+}
+
+impl HostError for HostCodeRevert {}
+
+impl TestRuntime {
+    fn read_memory(&self, ptr: u32, len: usize) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.resize(len, 0u8);
+
+        if let Err(e) = self.vm.memory.get_into(ptr, &mut buf) {
+            panic!("read memory: {}", e);
+        }
+
+        buf
+    }
+
+    fn read_key(&self, ptr: u32) -> [u8; 32] {
+        let mut key = [0u8; 32];
+
+        self.vm
+            .memory
+            .get_into(ptr, &mut key)
+            .expect("copy key from wasm memory");
+
+        key
+    }
+
+    fn read_address(&self, ptr: u32) -> Address {
+        let mut addr = [0u8; 20];
+
+        self.vm
+            .memory
+            .get_into(ptr, &mut addr)
+            .expect("copy address from wasm memory");
+
+        addr
+    }
+}
+
+impl Externals for TestRuntime {
+    fn invoke_index(
+        &mut self,
+        index: usize,
+        args: RuntimeArgs,
+    ) -> Result<Option<RuntimeValue>, Trap> {
+        match FromPrimitive::from_usize(index) {
+            Some(Extern::get_call_size) => Ok(Some(RuntimeValue::I32(self.vm.input.len() as i32))),
+            Some(Extern::get_return_size) => {
+                Ok(Some(RuntimeValue::I32(self.vm.returndata.len() as i32)))
+            }
+            Some(Extern::copy_call_value) => {
+                let from = args.nth_checked::<u32>(0)? as usize;
+                let to = args.nth_checked::<u32>(1)? as usize;
+                let dest = args.nth_checked::<u32>(2)?;
+
+                self.vm
+                    .memory
+                    .set(dest, &self.vm.input[from..to])
+                    .expect("copy_call_value should work");
+
+                Ok(None)
+            }
+            Some(Extern::copy_return_value) => {
+                let dest = args.nth_checked::<u32>(0)?;
+                let data_offset = args.nth_checked::<u32>(1)? as usize;
+                let data_len = args.nth_checked::<u32>(2)? as usize;
+
+                let data = &self.vm.returndata[data_offset..data_offset + data_len];
+
+                println!("copy_return_value {} {}", data_len, hex::encode(data));
+
+                self.vm
+                    .memory
+                    .set(dest, data)
+                    .expect("copy_return_value should work");
+
+                Ok(None)
+            }
+            Some(Extern::set_return) => {
+                let src: u32 = args.nth_checked(0)?;
+                let len: u32 = args.nth_checked(1)?;
+
+                self.vm.output = self.read_memory(src, len as usize);
+
+                println!("set_return: {} {}", len, hex::encode(&self.vm.output));
+
+                Ok(None)
+            }
+            Some(Extern::system_halt) => {
+                let code: u32 = args.nth_checked(0)?;
+
+                println!("system_halt: {}", code);
+
+                if code == 0 {
+                    Err(Trap::new(TrapKind::Host(Box::new(HostCodeFinish {}))))
+                } else {
+                    Err(Trap::new(TrapKind::Host(Box::new(HostCodeRevert {}))))
+                }
+            }
+            Some(Extern::load_storage) => {
+                let key_ptr: u32 = args.nth_checked(0)?;
+                let data_ptr: u32 = args.nth_checked(1)?;
+
+                let key = self.read_key(key_ptr);
+
+                let res = if let Some(v) = self.store.get(&(self.vm.cur, key)) {
+                    v
+                } else {
+                    &[0u8; 32]
+                };
+
+                println!(
+                    "load_storage {} -> {}",
+                    hex::encode(&key),
+                    hex::encode(&res)
+                );
+
+                self.vm
+                    .memory
+                    .set(data_ptr, res)
+                    .expect("copy value to wasm memory");
+
+                Ok(None)
+            }
+            Some(Extern::save_storage) => {
+                let key_ptr: u32 = args.nth_checked(0)?;
+                let data_ptr: u32 = args.nth_checked(1)?;
+
+                let key = self.read_key(key_ptr);
+                let mut data = [0u8; 32];
+
+                self.vm
+                    .memory
+                    .get_into(data_ptr, &mut data)
+                    .expect("copy value from wasm memory");
+
+                println!(
+                    "save_storage {} <- {}",
+                    hex::encode(&key),
+                    hex::encode(&data)
+                );
+
+                self.storage_writes += 1;
+
+                if data.iter().any(|n| *n != 0) {
+                    self.store.insert((self.vm.cur, key), data);
+                } else {
+                    self.store.remove(&(self.vm.cur, key));
+                }
+                Ok(None)
+            }
+            Some(Extern::get_storage_string_size) => {
+                let key_ptr: u32 = args.nth_checked(0)?;
+
+                let key = self.read_key(key_ptr);
+
+                let len = self
+                    .string_store
+                    .get(&(self.vm.cur, key))
+                    .map(|v| v.len())
+                    .unwrap_or(0);
+
+                println!("get_storage_string_size {} -> {}", hex::encode(&key), len);
+
+                Ok(Some(RuntimeValue::I32(len as i32)))
+            }
+            Some(Extern::load_storage_string) => {
+                let key_ptr: u32 = args.nth_checked(0)?;
+                let data_ptr: u32 = args.nth_checked(1)?;
+
+                let key = self.read_key(key_ptr);
+
+                let res = self
+                    .string_store
+                    .get(&(self.vm.cur, key))
+                    .cloned()
+                    .unwrap_or_default();
+
+                println!(
+                    "load_storage_string {} -> {}",
+                    hex::encode(&key),
+                    hex::encode(&res)
+                );
+
+                self.vm
+                    .memory
+                    .set(data_ptr, &res)
+                    .expect("copy string to wasm memory");
+
+                Ok(None)
+            }
+            Some(Extern::save_storage_string) => {
+                let key_ptr: u32 = args.nth_checked(0)?;
+                let data_ptr: u32 = args.nth_checked(1)?;
+                let len: u32 = args.nth_checked(2)?;
+
+                let key = self.read_key(key_ptr);
+                let data = self.read_memory(data_ptr, len as usize);
+
+                println!(
+                    "save_storage_string {} <- {}",
+                    hex::encode(&key),
+                    hex::encode(&data)
+                );
+
+                if data.is_empty() {
+                    self.string_store.remove(&(self.vm.cur, key));
+                } else {
+                    self.string_store.insert((self.vm.cur, key), data);
+                }
+
+                Ok(None)
+            }
+            Some(Extern::create) => {
+                //let value_ptr: u32 = args.nth_checked(0)?;
+                let input_ptr: u32 = args.nth_checked(1)?;
+                let input_len: u32 = args.nth_checked(2)?;
+                let address_ptr: u32 = args.nth_checked(3)?;
+
+                let buf = self.read_memory(input_ptr, input_len as usize);
+
+                println!("create code: {}", hex::encode(&buf));
+
+                let addr = address_new();
+                println!("create address: {}", hex::encode(&addr));
+
+                // the abi encoded constructor arguments are concatenated to the code, so
+                // find which code is was and use that instead
+                let code = self
+                    .contracts
+                    .iter()
+                    .find(|c| buf.starts_with(c))
+                    .unwrap()
+                    .clone();
+
+                let input = buf[code.len()..].to_vec();
+
+                self.accounts.insert(addr, (code, 0));
+
+                let mut vm = VirtualMachine::new(addr, self.vm.cur, 0);
+
+                std::mem::swap(&mut self.vm, &mut vm);
+
+                let success = self.execute("deploy", input);
+
+                std::mem::swap(&mut self.vm, &mut vm);
+
+                if !success {
+                    self.accounts.remove(&addr);
+
+                    return Ok(Some(RuntimeValue::I32(1)));
+                }
+
+                // the host appends the immutables returned by deploy to the code
+                self.accounts.get_mut(&addr).unwrap().0.extend(vm.output);
+
+                self.vm
+                    .memory
+                    .set(address_ptr, &addr[..])
+                    .expect("copy address to wasm memory");
+
+                Ok(Some(RuntimeValue::I32(0)))
+            }
+            Some(ext @ Extern::invoke_contract)
+            | Some(ext @ Extern::invoke_static_contract)
+            | Some(ext @ Extern::invoke_delegate_contract) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+                let input_len: u32 = args.nth_checked(1)?;
+                let input_ptr: u32 = args.nth_checked(2)?;
+                //let value_ptr: u32 = args.nth_checked(3)?;
+                //let gas_ptr: u32 = args.nth_checked(4)?;
+
+                let buf = self.read_memory(input_ptr, input_len as usize);
+                let addr = self.read_address(address_ptr);
+
+                println!(
+                    "invoke_contract address: {} data: {}",
+                    hex::encode(&addr),
+                    hex::encode(&buf)
+                );
+
+                let code = self
+                    .accounts
+                    .get(&addr)
+                    .map(|a| a.0.clone())
+                    .unwrap_or_default();
+
+                // calling an account without code succeeds, and returns nothing
+                if code.is_empty() {
+                    self.vm.returndata = Vec::new();
+
+                    return Ok(Some(RuntimeValue::I32(0)));
+                }
+
+                // delegatecall runs the callee code in our context, so it sees our
+                // storage, caller and value
+                let mut vm = if matches!(ext, Extern::invoke_delegate_contract) {
+                    VirtualMachine::new(self.vm.cur, self.vm.caller, self.vm.value)
+                } else {
+                    VirtualMachine::new(addr, self.vm.cur, 0)
+                };
+
+                std::mem::swap(&mut self.vm, &mut vm);
+
+                self.vm.input = buf;
+
+                let module = self.create_module(&code);
+
+                if let Some(ExternVal::Memory(memory_ref)) = module.export_by_name("memory") {
+                    self.vm.memory = memory_ref;
+                }
+
+                let ret = match module.invoke_export("start", &[], self) {
+                    Err(wasmi::Error::Trap(trap)) => match trap.kind() {
+                        TrapKind::Host(kind) => {
+                            if format!("{}", kind) == "revert" {
+                                1
+                            } else {
+                                0
+                            }
+                        }
+                        _ => panic!("fail to invoke start via invoke_contract: {}", trap),
+                    },
+                    Ok(_) => 0,
+                    Err(e) => panic!("fail to invoke start via invoke_contract: {}", e),
+                };
+
+                let res = self.vm.output.clone();
+
+                std::mem::swap(&mut self.vm, &mut vm);
+
+                self.vm.returndata = res;
+
+                Ok(Some(RuntimeValue::I32(ret)))
+            }
+            Some(Extern::transfer) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+                let value_ptr: u32 = args.nth_checked(1)?;
+
+                let addr = self.read_address(address_ptr);
+                let value = self.read_memory(value_ptr, 32);
+
+                // value is passed big endian
+                let mut amount = [0u8; 16];
+                amount.copy_from_slice(&value[16..]);
+                let amount = u128::from_be_bytes(amount);
+
+                println!("transfer: {} {}", hex::encode(&addr), amount);
+
+                let balance = self.accounts.get(&self.vm.cur).map(|a| a.1).unwrap_or(0);
+
+                if balance < amount {
+                    return Ok(Some(RuntimeValue::I32(1)));
+                }
+
+                if let Some(account) = self.accounts.get_mut(&self.vm.cur) {
+                    account.1 -= amount;
+                }
+
+                self.accounts.entry(addr).or_insert((Vec::new(), 0)).1 += amount;
+
+                Ok(Some(RuntimeValue::I32(0)))
+            }
+            Some(Extern::get_msgvalue) => {
+                let value_ptr: u32 = args.nth_checked(0)?;
+
+                let mut value = [0u8; 32];
+                value[..16].copy_from_slice(&self.vm.value.to_le_bytes());
+
+                println!("get_msgvalue: {}", hex::encode(&value));
+
+                self.vm.memory.set(value_ptr, &value).expect("set value");
+
+                Ok(None)
+            }
+            Some(Extern::get_address) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+
+                println!("get_address: {}", hex::encode(&self.vm.cur));
+
+                self.vm
+                    .memory
+                    .set(address_ptr, &self.vm.cur[..])
+                    .expect("set address");
+
+                Ok(None)
+            }
+            Some(Extern::get_sender) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+
+                println!("get_sender: {}", hex::encode(&self.vm.caller));
+
+                self.vm
+                    .memory
+                    .set(address_ptr, &self.vm.caller[..])
+                    .expect("set sender");
+
+                Ok(None)
+            }
+            Some(Extern::get_tx_origin) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+
+                self.vm
+                    .memory
+                    .set(address_ptr, &self.origin[..])
+                    .expect("set origin");
+
+                Ok(None)
+            }
+            Some(Extern::get_external_balance) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+                let balance_ptr: u32 = args.nth_checked(1)?;
+
+                let addr = self.read_address(address_ptr);
+
+                let mut value = [0u8; 32];
+                value[..16].copy_from_slice(
+                    &self
+                        .accounts
+                        .get(&addr)
+                        .map(|a| a.1)
+                        .unwrap_or(0)
+                        .to_le_bytes(),
+                );
+
+                println!(
+                    "get_external_balance: {} {}",
+                    hex::encode(&addr),
+                    hex::encode(&value)
+                );
+
+                self.vm
+                    .memory
+                    .set(balance_ptr, &value)
+                    .expect("set balance");
+
+                Ok(None)
+            }
+            Some(Extern::get_external_code_size) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+
+                let addr = self.read_address(address_ptr);
+
+                let size = self.accounts.get(&addr).map(|a| a.0.len()).unwrap_or(0);
+
+                println!("get_external_code_size: {} {}", hex::encode(&addr), size);
+
+                Ok(Some(RuntimeValue::I32(size as i32)))
+            }
+            Some(Extern::copy_external_code) => {
+                let address_ptr: u32 = args.nth_checked(0)?;
+                let dest_ptr: u32 = args.nth_checked(1)?;
+                let offset: u32 = args.nth_checked(2)?;
+                let len: u32 = args.nth_checked(3)?;
+
+                let addr = self.read_address(address_ptr);
+
+                let code = self
+                    .accounts
+                    .get(&addr)
+                    .map(|a| a.0.clone())
+                    .unwrap_or_default();
+
+                let offset = offset as usize;
+                let len = len as usize;
+
+                assert!(len > 0, "copy_external_code with zero length");
+                assert!(offset + len <= code.len());
+
+                self.vm
+                    .memory
+                    .set(dest_ptr, &code[offset..offset + len])
+                    .expect("copy external code");
+
+                Ok(None)
+            }
+            Some(Extern::get_gas_left) => {
+                let ptr: u32 = args.nth_checked(0)?;
+
+                self.vm
+                    .memory
+                    .set(ptr, &2_224_000u64.to_le_bytes())
+                    .expect("set gas left");
+
+                Ok(None)
+            }
+            Some(Extern::get_block_number) => {
+                let ptr: u32 = args.nth_checked(0)?;
+
+                self.vm
+                    .memory
+                    .set(ptr, &950_277u64.to_le_bytes())
+                    .expect("set block number");
+
+                Ok(None)
+            }
+            Some(Extern::get_block_timestamp) => {
+                let ptr: u32 = args.nth_checked(0)?;
+
+                self.vm
+                    .memory
+                    .set(ptr, &1_594_035_638u64.to_le_bytes())
+                    .expect("set block timestamp");
+
+                Ok(None)
+            }
+            Some(Extern::get_chain_id) => {
+                let ptr: u32 = args.nth_checked(0)?;
+
+                self.vm
+                    .memory
+                    .set(ptr, &225u64.to_le_bytes())
+                    .expect("set chain id");
+
+                Ok(None)
+            }
+            Some(Extern::crypto_keccak256)
+            | Some(Extern::crypto_sha256)
+            | Some(Extern::crypto_ripemd160) => {
+                let data_ptr: u32 = args.nth_checked(0)?;
+                let len: u32 = args.nth_checked(1)?;
+                let result_ptr: u32 = args.nth_checked(2)?;
+
+                let data = self.read_memory(data_ptr, len as usize);
+
+                let hash = match FromPrimitive::from_usize(index) {
+                    Some(Extern::crypto_keccak256) => {
+                        let mut hasher = Keccak::v256();
+                        let mut hash = [0u8; 32];
+                        hasher.update(&data);
+                        hasher.finalize(&mut hash);
+                        hash.to_vec()
+                    }
+                    Some(Extern::crypto_sha256) => {
+                        let mut hasher = Sha256::new();
+                        hasher.update(&data);
+                        hasher.finalize().to_vec()
+                    }
+                    _ => {
+                        let mut hasher = Ripemd160::new();
+                        hasher.update(&data);
+                        hasher.finalize().to_vec()
+                    }
+                };
+
+                println!("hash: {} -> {}", hex::encode(&data), hex::encode(&hash));
+
+                self.vm.memory.set(result_ptr, &hash).expect("set hash");
+
+                Ok(None)
+            }
+            Some(Extern::crypto_recover) => {
+                let hash_ptr: u32 = args.nth_checked(0)?;
+                let v: u32 = args.nth_checked(1)?;
+                let r_ptr: u32 = args.nth_checked(2)?;
+                let s_ptr: u32 = args.nth_checked(3)?;
+                let result_ptr: u32 = args.nth_checked(4)?;
+
+                let hash = self.read_memory(hash_ptr, 32);
+                let mut signature = self.read_memory(r_ptr, 32);
+                signature.extend(self.read_memory(s_ptr, 32));
+
+                let mut message = [0u8; 32];
+                message.copy_from_slice(&hash);
+
+                // like the ecrecover precompile, return the zero address on any failure
+                let recovered = (v as u8)
+                    .checked_sub(27)
+                    .and_then(|id| libsecp256k1::RecoveryId::parse(id).ok())
+                    .zip(libsecp256k1::Signature::parse_standard_slice(&signature).ok())
+                    .and_then(|(id, signature)| {
+                        libsecp256k1::recover(
+                            &libsecp256k1::Message::parse(&message),
+                            &signature,
+                            &id,
+                        )
+                        .ok()
+                    });
+
+                let mut addr = [0u8; 20];
+
+                if let Some(public_key) = recovered {
+                    let mut hasher = Keccak::v256();
+                    let mut hash = [0u8; 32];
+                    hasher.update(&public_key.serialize()[1..]);
+                    hasher.finalize(&mut hash);
+
+                    addr.copy_from_slice(&hash[12..]);
+                }
+
+                println!(
+                    "crypto_recover: {} {} {} -> {}",
+                    hex::encode(&hash),
+                    v,
+                    hex::encode(&signature),
+                    hex::encode(&addr)
+                );
+
+                self.vm.memory.set(result_ptr, &addr).expect("set address");
+
+                Ok(None)
+            }
+            Some(Extern::write_log) => {
+                let data_ptr: u32 = args.nth_checked(0)?;
+                let data_len: u32 = args.nth_checked(1)?;
+
+                let data = self.read_memory(data_ptr, data_len as usize);
+
+                println!("write_log: data: {}", hex::encode(&data));
+
+                self.events.push(data);
+
+                Ok(None)
+            }
+            _ => panic!("external {} unknown", index),
+        }
+    }
+}
+
+impl ModuleImportResolver for TestRuntime {
+    fn resolve_func(&self, field_name: &str, signature: &Signature) -> Result<FuncRef, Error> {
+        let index = match field_name {
+            "get_call_size" => Extern::get_call_size,
+            "copy_call_value" => Extern::copy_call_value,
+            "load_storage" => Extern::load_storage,
+            "save_storage" => Extern::save_storage,
+            "load_storage_string" => Extern::load_storage_string,
+            "save_storage_string" => Extern::save_storage_string,
+            "get_storage_string_size" => Extern::get_storage_string_size,
+            "set_return" => Extern::set_return,
+            "system_halt" => Extern::system_halt,
+            "create" => Extern::create,
+            "invoke_contract" => Extern::invoke_contract,
+            "invoke_static_contract" => Extern::invoke_static_contract,
+            "invoke_delegate_contract" => Extern::invoke_delegate_contract,
+            "transfer" => Extern::transfer,
+            "copy_return_value" => Extern::copy_return_value,
+            "get_return_size" => Extern::get_return_size,
+            "get_msgvalue" => Extern::get_msgvalue,
+            "get_address" => Extern::get_address,
+            "get_sender" => Extern::get_sender,
+            "get_external_balance" => Extern::get_external_balance,
+            "get_external_code_size" => Extern::get_external_code_size,
+            "copy_external_code" => Extern::copy_external_code,
+            "get_gas_left" => Extern::get_gas_left,
+            "get_block_number" => Extern::get_block_number,
+            "get_block_timestamp" => Extern::get_block_timestamp,
+            "get_chain_id" => Extern::get_chain_id,
+            "get_tx_origin" => Extern::get_tx_origin,
+            "crypto_keccak256" => Extern::crypto_keccak256,
+            "crypto_sha256" => Extern::crypto_sha256,
+            "crypto_ripemd160" => Extern::crypto_ripemd160,
+            "crypto_recover" => Extern::crypto_recover,
+            "write_log" => Extern::write_log,
+            _ => {
+                panic!("{} not implemented", field_name);
+            }
+        };
+
+        Ok(FuncInstance::alloc_host(signature.clone(), index as usize))
+    }
+}
+
+impl TestRuntime {
+    fn create_module(&self, code: &[u8]) -> ModuleRef {
+        // the immutables returned by deploy are appended to the code, so find the wasm
+        let code = self
+            .contracts
+            .iter()
+            .find(|c| code.starts_with(c))
+            .expect("code should be one of the contracts");
+
+        let module = Module::from_buffer(&code).expect("parse wasm should work");
+
+        ModuleInstance::new(&module, &ImportsBuilder::new().with_resolver("env", self))
+            .expect("Failed to instantiate module")
+            .run_start(&mut NopExternals)
+            .expect("Failed to run start function in module")
+    }
+
+    /// Invoke the start function with the given calldata, and return true if it finished
+    /// or false if it reverted
+    fn invoke(&mut self, calldata: Vec<u8>) -> bool {
+        self.vm.value = self.value;
+
+        self.execute("start", calldata)
+    }
+
+    /// Invoke an exported function of the current contract with the given calldata, and
+    /// return true if it finished or false if it reverted
+    fn execute(&mut self, export: &str, calldata: Vec<u8>) -> bool {
+        let code = self.accounts[&self.vm.cur].0.clone();
+
+        let module = self.create_module(&code);
+
+        println!("CALLDATA: {}", hex::encode(&calldata));
+
+        self.vm.input = calldata;
+
+        if let Some(ExternVal::Memory(memory_ref)) = module.export_by_name("memory") {
+            self.vm.memory = memory_ref;
+        }
+
+        let res = match module.invoke_export(export, &[], self) {
+            Err(wasmi::Error::Trap(trap)) => match trap.kind() {
+                TrapKind::Host(host_error) => host_error.downcast_ref::<HostCodeRevert>().is_none(),
+                _ => panic!("fail to invoke {}: {}", export, trap),
+            },
+            Ok(_) => panic!("{} should not return", export),
+            Err(e) => panic!("fail to invoke {}: {}", export, e),
+        };
+
+        println!("RETURNDATA: {}", hex::encode(&self.vm.output));
+
+        res
+    }
+
+    fn function(&mut self, name: &str, args: &[Token]) -> Vec<Token> {
+        let calldata = match self.abi.functions[name][0].encode_input(args) {
+            Ok(n) => n,
+            Err(x) => panic!("{}", x),
+        };
+
+        if !self.invoke(calldata) {
+            panic!("function {} reverted", name);
+        }
+
+        self.abi.functions[name][0]
+            .decode_output(&self.vm.output)
+            .unwrap()
+    }
+
+    fn constructor(&mut self, args: &[Token]) {
+        self.vm.cur = address_new();
+
+        self.accounts
+            .insert(self.vm.cur, (self.contracts.last().unwrap().clone(), 0));
+
+        let calldata = match &self.abi.constructor {
+            Some(constructor) => constructor.encode_input(Vec::new(), args).unwrap(),
+            None => Vec::new(),
+        };
+
+        self.vm.value = self.value;
+
+        if !self.execute("deploy", calldata) {
+            panic!("constructor reverted");
+        }
+
+        let immutables = self.vm.output.clone();
+
+        self.accounts
+            .get_mut(&self.vm.cur)
+            .unwrap()
+            .0
+            .extend(immutables);
+    }
+
+    fn constructor_expect_revert(&mut self, args: &[Token]) {
+        self.vm.cur = address_new();
+
+        self.accounts
+            .insert(self.vm.cur, (self.contracts.last().unwrap().clone(), 0));
+
+        let calldata = match &self.abi.constructor {
+            Some(constructor) => constructor.encode_input(Vec::new(), args).unwrap(),
+            None => Vec::new(),
+        };
+
+        self.vm.value = self.value;
+
+        if self.execute("deploy", calldata) {
+            panic!("constructor did not revert");
+        }
+
+        // the contract is not deployed
+        self.accounts.remove(&self.vm.cur);
+    }
+}
+
+fn build_solidity(src: &str) -> TestRuntime {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (res, ns) = compile(
+        "test.sol",
+        &mut cache,
+        inkwell::OptimizationLevel::Default,
+        Target::Lachain,
+        false,
+    );
+
+    diagnostics::print_messages(&cache, &ns, false);
+
+    test_runtime(res)
+}
+
+fn build_solidity_with_options(src: &str, opt: &Options) -> TestRuntime {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let mut ns = solang::parse_and_resolve("test.sol", &mut cache, Target::Lachain);
+
+    if !diagnostics::any_errors(&ns.diagnostics) {
+        codegen(&mut ns, opt);
+    }
+
+    diagnostics::print_messages(&cache, &ns, false);
+
+    let res = (0..ns.contracts.len())
+        .filter(|c| ns.contracts[*c].is_concrete() && !ns.contracts[*c].code.is_empty())
+        .map(|c| {
+            let code = &ns.contracts[c].code;
+            let (abi, _) = generate_abi(c, &ns, code, false);
+
+            (code.clone(), abi)
+        })
+        .collect();
+
+    test_runtime(res)
+}
+
+/// The textual LLVM IR of a contract, before and after optimization
+struct LlvmIr {
+    unoptimized: String,
+    optimized: String,
+}
+
+/// Compile the source for Lachain, and return the LLVM IR of the last contract
+fn build_llvm_ir(src: &str, opt: &Options) -> LlvmIr {
+    let filename = "test.sol";
+
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents(filename, src.to_string());
+
+    let mut ns = solang::parse_and_resolve(filename, &mut cache, Target::Lachain);
+
+    codegen(&mut ns, opt);
+
+    diagnostics::print_messages(&cache, &ns, false);
+
+    assert!(!diagnostics::any_errors(&ns.diagnostics));
+
+    let context = inkwell::context::Context::create();
+
+    let contract = ns.contracts.last().unwrap();
+
+    let binary = contract.emit(&ns, &context, filename, opt);
+
+    let unoptimized = binary.module.print_to_string().to_string();
+
+    // this runs the optimization passes on the module
+    binary.code(Generate::Linked).expect("llvm build");
+
+    let optimized = binary.module.print_to_string().to_string();
+
+    LlvmIr {
+        unoptimized,
+        optimized,
+    }
+}
+
+fn test_runtime(res: Vec<(Vec<u8>, String)>) -> TestRuntime {
+    for v in &res {
+        println!("contract size:{}", v.0.len());
+    }
+
+    assert!(!res.is_empty());
+
+    // resolve
+    let (_, abi) = res.last().unwrap().clone();
+
+    let origin = address_new();
+
+    TestRuntime {
+        accounts: HashMap::new(),
+        vm: VirtualMachine::new([0u8; 20], origin, 0),
+        origin,
+        value: 0,
+        store: HashMap::new(),
+        string_store: HashMap::new(),
+        events: Vec::new(),
+        storage_writes: 0,
+        abi: ethabi::Contract::load(abi.as_bytes()).unwrap(),
+        contracts: res.into_iter().map(|v| v.0).collect(),
+    }
+}
+
+pub fn parse_and_resolve(src: &'static str, target: Target) -> ast::Namespace {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    solang::parse_and_resolve("test.sol", &mut cache, target)
+}
+
+pub fn first_error(errors: Vec<ast::Diagnostic>) -> String {
+    match errors.iter().find(|m| m.level == ast::Level::Error) {
+        Some(m) => m.message.to_owned(),
+        None => panic!("no errors found"),
+    }
+}
+
+pub fn first_warning(errors: Vec<ast::Diagnostic>) -> String {
+    match errors.iter().find(|m| m.level == ast::Level::Warning) {
+        Some(m) => m.message.to_owned(),
+        None => panic!("no warnings found"),
+    }
+}
+
+pub fn no_errors(errors: Vec<ast::Diagnostic>) {
+    assert!(
+        errors
+            .iter()
+            .filter(|m| m.level == ast::Level::Error)
+            .count()
+            == 0
+    );
+}
+
+#[test]
+fn simple_solidiy_compile_and_run() {
+    // parse
+    let mut runtime = build_solidity(
+        "
+        contract test {
+            function foo() public returns (uint32) {
+                return 2;
+            }
+        }",
+    );
+
+    // call constructor
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("foo", &[]);
+
+    assert_eq!(
+        returns,
+        vec![ethabi::Token::Uint(ethereum_types::U256::from(2))]
+    );
+}
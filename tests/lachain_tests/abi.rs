@@ -0,0 +1,848 @@
+use crate::{build_solidity, build_solidity_with_options, parse_and_resolve};
+use ethabi::Token;
+use ethereum_types::U256;
+use solang::abi::ethereum::gen_abi;
+use solang::codegen::Options;
+use solang::Target;
+use tiny_keccak::{Hasher, Keccak};
+
+#[test]
+fn struct_array_param() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            struct Item {
+                uint64 id;
+                bool active;
+            }
+
+            function sum_active(Item[] memory items) public pure returns (uint64 sum, uint32 count) {
+                for (uint32 i = 0; i < items.length; i++) {
+                    if (items[i].active) {
+                        sum += items[i].id;
+                        count += 1;
+                    }
+                }
+            }
+
+            function flip(Item[] memory items) public pure returns (Item[] memory) {
+                for (uint32 i = 0; i < items.length; i++) {
+                    items[i].active = !items[i].active;
+                }
+
+                return items;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let items = Token::Array(vec![
+        Token::Tuple(vec![Token::Uint(U256::from(10)), Token::Bool(true)]),
+        Token::Tuple(vec![Token::Uint(U256::from(200)), Token::Bool(false)]),
+        Token::Tuple(vec![Token::Uint(U256::from(3000)), Token::Bool(true)]),
+    ]);
+
+    let returns = runtime.function("sum_active", &[items.clone()]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(3010)), Token::Uint(U256::from(2))]
+    );
+
+    let returns = runtime.function("flip", &[items]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Array(vec![
+            Token::Tuple(vec![Token::Uint(U256::from(10)), Token::Bool(false)]),
+            Token::Tuple(vec![Token::Uint(U256::from(200)), Token::Bool(true)]),
+            Token::Tuple(vec![Token::Uint(U256::from(3000)), Token::Bool(false)]),
+        ])]
+    );
+
+    let returns = runtime.function("sum_active", &[Token::Array(vec![])]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(0)), Token::Uint(U256::from(0))]
+    );
+}
+
+#[test]
+fn nested_dynamic_arrays() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function build() public pure returns (bytes) {
+                uint256[][] memory a = new uint256[][](2);
+
+                a[0] = new uint256[](2);
+                a[0][0] = 1;
+                a[0][1] = 2;
+
+                a[1] = new uint256[](1);
+                a[1][0] = 3;
+
+                return abi.encode(a);
+            }
+
+            function reencode(uint256[][] memory a) public pure returns (bytes) {
+                return abi.encode(a);
+            }
+
+            function sum(bytes memory data) public pure returns (uint256 total) {
+                uint256[][] memory a = abi.decode(data, (uint256[][]));
+
+                for (uint32 i = 0; i < a.length; i++) {
+                    for (uint32 j = 0; j < a[i].length; j++) {
+                        total += a[i][j];
+                    }
+                }
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let expected = Token::Array(vec![
+        Token::Array(vec![Token::Uint(U256::from(1)), Token::Uint(U256::from(2))]),
+        Token::Array(vec![Token::Uint(U256::from(3))]),
+    ]);
+
+    let encoded = ethabi::encode(&[expected.clone()]);
+
+    let returns = runtime.function("build", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(encoded.clone())]);
+
+    let returns = runtime.function("reencode", &[expected]);
+
+    assert_eq!(returns, vec![Token::Bytes(encoded.clone())]);
+
+    let returns = runtime.function("sum", &[Token::Bytes(encoded)]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(6))]);
+}
+
+#[test]
+fn keccak256_encoded_struct() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            struct S {
+                uint256 id;
+                bool flag;
+                address owner;
+            }
+
+            struct D {
+                uint64 id;
+                string name;
+            }
+
+            function hash_static(address owner) public pure returns (bytes32) {
+                S memory s = S({ id: 102, flag: true, owner: owner });
+
+                return keccak256(abi.encode(s));
+            }
+
+            function hash_dynamic() public pure returns (bytes32) {
+                D memory d = D({ id: 7, name: "lachain" });
+
+                return keccak256(abi.encode(d));
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let keccak256 = |data: &[u8]| {
+        let mut hasher = Keccak::v256();
+        let mut hash = [0u8; 32];
+
+        hasher.update(data);
+        hasher.finalize(&mut hash);
+
+        hash.to_vec()
+    };
+
+    let owner = ethereum_types::Address::from([0x42; 20]);
+
+    let expected = keccak256(&ethabi::encode(&[Token::Tuple(vec![
+        Token::Uint(U256::from(102)),
+        Token::Bool(true),
+        Token::Address(owner),
+    ])]));
+
+    let returns = runtime.function("hash_static", &[Token::Address(owner)]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(expected)]);
+
+    let expected = keccak256(&ethabi::encode(&[Token::Tuple(vec![
+        Token::Uint(U256::from(7)),
+        Token::String(String::from("lachain")),
+    ])]));
+
+    let returns = runtime.function("hash_dynamic", &[]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(expected)]);
+}
+
+#[test]
+fn corrupt_string_offset() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function len(string memory s) public pure returns (uint32) {
+                return uint32(bytes(s).length);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let calldata = runtime.abi.functions["len"][0]
+        .encode_input(&[Token::String("hello".to_string())])
+        .unwrap();
+
+    // selector, offset, length and one word of string data
+    assert_eq!(calldata.len(), 4 + 3 * 32);
+    assert!(runtime.invoke(calldata.clone()));
+
+    // the offset points past the end of the calldata
+    let mut corrupt = calldata.clone();
+    corrupt[4 + 31] = 0x80;
+    assert!(!runtime.invoke(corrupt));
+
+    // the offset is in range, but the string length runs past the end of the calldata
+    let mut corrupt = calldata;
+    corrupt[4 + 32 + 31] = 0x21;
+    assert!(!runtime.invoke(corrupt));
+}
+
+#[test]
+fn strict_decode() {
+    let src = r##"
+        contract c {
+            function f(bool b, uint8 x) public pure returns (bool, uint8) {
+                return (b, x);
+            }
+        }"##;
+
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            strict_abi_decode: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("f", &[Token::Bool(true), Token::Uint(U256::from(255))]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Bool(true), Token::Uint(U256::from(255))]
+    );
+
+    let calldata = runtime.abi.functions["f"][0]
+        .encode_input(&[Token::Bool(false), Token::Uint(U256::from(7))])
+        .unwrap();
+
+    assert!(runtime.invoke(calldata.clone()));
+
+    // a bool with value 2
+    let mut corrupt = calldata.clone();
+    corrupt[4 + 31] = 2;
+    assert!(!runtime.invoke(corrupt));
+
+    // a bool with a high bit set
+    let mut corrupt = calldata.clone();
+    corrupt[4] = 0x80;
+    assert!(!runtime.invoke(corrupt));
+
+    // a uint8 with value 0x107
+    let mut corrupt = calldata.clone();
+    corrupt[4 + 32 + 30] = 1;
+    assert!(!runtime.invoke(corrupt));
+
+    // without strict decoding, the high bits are ignored
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    let mut corrupt = calldata;
+    corrupt[4 + 31] = 2;
+    corrupt[4 + 32 + 30] = 1;
+    assert!(runtime.invoke(corrupt));
+
+    let returns = runtime.abi.functions["f"][0]
+        .decode_output(&runtime.vm.output)
+        .unwrap();
+
+    assert_eq!(returns, vec![Token::Bool(true), Token::Uint(U256::from(7))]);
+}
+
+#[test]
+fn encode_two_strings() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function enc(string a, string b) public pure returns (bytes) {
+                return abi.encode(a, b);
+            }
+
+            function round_trip(string a, string b) public pure returns (string, string) {
+                (string memory x, string memory y) = abi.decode(abi.encode(a, b), (string, string));
+
+                return (x, y);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let a = "the first string is longer than thirty-two bytes";
+    let b = "short";
+
+    let returns = runtime.function(
+        "enc",
+        &[Token::String(a.to_string()), Token::String(b.to_string())],
+    );
+
+    let encoded = match &returns[0] {
+        Token::Bytes(encoded) => encoded.clone(),
+        _ => unreachable!(),
+    };
+
+    // the second offset skips the heads, the first length word and the padded first string
+    assert_eq!(encoded.len(), 64 + 32 + 64 + 32 + 32);
+    assert_eq!(U256::from_big_endian(&encoded[0..32]), U256::from(64));
+    assert_eq!(
+        U256::from_big_endian(&encoded[32..64]),
+        U256::from(64 + 32 + 64)
+    );
+
+    assert_eq!(
+        ethabi::decode(
+            &[ethabi::ParamType::String, ethabi::ParamType::String],
+            &encoded
+        )
+        .unwrap(),
+        vec![Token::String(a.to_string()), Token::String(b.to_string())]
+    );
+
+    let returns = runtime.function(
+        "round_trip",
+        &[Token::String(b.to_string()), Token::String(a.to_string())],
+    );
+
+    assert_eq!(
+        returns,
+        vec![Token::String(b.to_string()), Token::String(a.to_string())]
+    );
+}
+
+#[test]
+fn return_struct() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            struct MyStruct {
+                uint256 amount;
+                bool flag;
+            }
+
+            function get(uint256 amount) public pure returns (MyStruct memory) {
+                return MyStruct({ amount: amount * 2, flag: amount > 10 });
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("get", &[Token::Uint(U256::from(21))]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Tuple(vec![
+            Token::Uint(U256::from(42)),
+            Token::Bool(true)
+        ])]
+    );
+
+    let returns = runtime.function("get", &[Token::Uint(U256::from(3))]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Tuple(vec![
+            Token::Uint(U256::from(6)),
+            Token::Bool(false)
+        ])]
+    );
+}
+
+#[test]
+fn decode_non_canonical_bool() {
+    let src = r##"
+        contract c {
+            function dec(bytes b) public pure returns (bool) {
+                return abi.decode(b, (bool));
+            }
+        }"##;
+
+    let two = {
+        let mut word = vec![0u8; 32];
+        word[31] = 2;
+        word
+    };
+
+    let high_bit = {
+        let mut word = vec![0u8; 32];
+        word[0] = 0x80;
+        word
+    };
+
+    // lenient: any non-zero word is true
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    for word in &[two.clone(), high_bit.clone()] {
+        let returns = runtime.function("dec", &[Token::Bytes(word.clone())]);
+
+        assert_eq!(returns, vec![Token::Bool(true)]);
+    }
+
+    let returns = runtime.function("dec", &[Token::Bytes(vec![0u8; 32])]);
+
+    assert_eq!(returns, vec![Token::Bool(false)]);
+
+    // strict: only 0 and 1 are accepted
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            strict_abi_decode: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    for word in &[two, high_bit] {
+        let calldata = runtime.abi.functions["dec"][0]
+            .encode_input(&[Token::Bytes(word.clone())])
+            .unwrap();
+
+        assert!(!runtime.invoke(calldata));
+    }
+
+    let mut one = vec![0u8; 32];
+    one[31] = 1;
+
+    let returns = runtime.function("dec", &[Token::Bytes(one)]);
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+}
+
+#[test]
+fn evm_compatible_return() {
+    // return data is abi encoded exactly like an evm node would return it from eth_call
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function get() public view returns (string, uint256) {
+                return ("hello", 42);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let calldata = runtime.abi.functions["get"][0].encode_input(&[]).unwrap();
+
+    assert!(runtime.invoke(calldata));
+
+    // output of eth_call on an evm chain for the same contract
+    let expected = hex::decode(concat!(
+        "0000000000000000000000000000000000000000000000000000000000000040",
+        "000000000000000000000000000000000000000000000000000000000000002a",
+        "0000000000000000000000000000000000000000000000000000000000000005",
+        "68656c6c6f000000000000000000000000000000000000000000000000000000",
+    ))
+    .unwrap();
+
+    assert_eq!(runtime.vm.output, expected);
+}
+
+#[test]
+fn encode_no_args() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function empty() public pure returns (bytes) {
+                return abi.encode();
+            }
+
+            function empty_len() public pure returns (uint32) {
+                bytes memory b = abi.encode();
+
+                return uint32(b.length);
+            }
+
+            function hash() public pure returns (bytes32) {
+                return keccak256(abi.encode());
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("empty", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(Vec::new())]);
+
+    let returns = runtime.function("empty_len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(0))]);
+
+    // keccak256 of nothing
+    let mut hash = [0u8; 32];
+    Keccak::v256().finalize(&mut hash);
+
+    let returns = runtime.function("hash", &[]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(hash.to_vec())]);
+}
+
+#[test]
+fn encode_call() {
+    let mut runtime = build_solidity(
+        r##"
+        interface IERC20 {
+            function transfer(address to, uint256 amount) external returns (bool);
+        }
+
+        contract c {
+            function encode_call(address to, uint256 amt) public pure returns (bytes) {
+                return abi.encodeCall(IERC20.transfer, (to, amt));
+            }
+
+            function encode_selector(address to, uint256 amt) public pure returns (bytes) {
+                return abi.encodeWithSelector(bytes4(0xa9059cbb), to, amt);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let args = [
+        Token::Address(ethereum_types::Address::from([0x42; 20])),
+        Token::Uint(U256::from(1000)),
+    ];
+
+    let call = runtime.function("encode_call", &args);
+    let selector = runtime.function("encode_selector", &args);
+
+    assert_eq!(call, selector);
+
+    let mut expected = vec![0xa9, 0x05, 0x9c, 0xbb];
+    expected.extend(ethabi::encode(&args));
+
+    assert_eq!(call, vec![Token::Bytes(expected)]);
+}
+
+#[test]
+fn keccak_encode_mapping_key() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            mapping(bytes32 => uint64) balances;
+
+            function key(address owner, uint64 id) public pure returns (bytes32) {
+                return keccak256(abi.encode(owner, id));
+            }
+
+            function set(address owner, uint64 id, uint64 value) public {
+                balances[keccak256(abi.encode(owner, id))] = value;
+            }
+
+            function get(bytes32 k) public view returns (uint64) {
+                return balances[k];
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let args = [
+        Token::Address(ethereum_types::Address::from([0x42; 20])),
+        Token::Uint(U256::from(7)),
+    ];
+
+    // both members are padded to 32 bytes, unlike abi.encodePacked
+    let encoded = ethabi::encode(&args);
+    assert_eq!(encoded.len(), 64);
+
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(&encoded);
+    hasher.finalize(&mut hash);
+
+    let returns = runtime.function("key", &args);
+
+    assert_eq!(returns, vec![Token::FixedBytes(hash.to_vec())]);
+
+    runtime.function(
+        "set",
+        &[
+            args[0].clone(),
+            args[1].clone(),
+            Token::Uint(U256::from(102)),
+        ],
+    );
+
+    let returns = runtime.function("get", &[Token::FixedBytes(hash.to_vec())]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(102))]);
+}
+
+#[test]
+fn decode_overlapping_offsets() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function f(bytes a, bytes b) public pure returns (bytes, bytes) {
+                a[0] = 0xff;
+
+                return (a, b);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // both offsets point at the same length and data
+    let mut calldata = runtime.abi.functions["f"][0].short_signature().to_vec();
+
+    calldata.extend(ethabi::encode(&[
+        Token::Uint(U256::from(0x40)),
+        Token::Uint(U256::from(0x40)),
+        Token::Uint(U256::from(3)),
+    ]));
+    calldata.extend(b"abc");
+    calldata.resize(calldata.len() + 29, 0);
+
+    assert!(runtime.invoke(calldata));
+
+    let returns = runtime.abi.functions["f"][0]
+        .decode_output(&runtime.vm.output)
+        .unwrap();
+
+    // each bytes argument is a copy, so writing to one does not change the other
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bytes(vec![0xff, b'b', b'c']),
+            Token::Bytes(b"abc".to_vec())
+        ]
+    );
+}
+
+#[test]
+fn long_revert_reason() {
+    let reason = "this revert reason is deliberately long, so that it spans more than three words of abi encoded data.";
+
+    assert_eq!(reason.len(), 100);
+
+    let mut runtime = build_solidity(&format!(
+        r##"
+        contract c {{
+            function f() public pure {{
+                revert("{}");
+            }}
+        }}"##,
+        reason
+    ));
+
+    runtime.constructor(&[]);
+
+    let calldata = runtime.abi.functions["f"][0].encode_input(&[]).unwrap();
+
+    assert!(!runtime.invoke(calldata));
+
+    // selector, offset, length and the string padded to four words
+    assert_eq!(runtime.vm.output.len(), 4 + 32 + 32 + 128);
+    assert_eq!(&runtime.vm.output[..4], &[0x08, 0xc3, 0x79, 0xa0]);
+
+    let decoded = ethabi::decode(&[ethabi::ParamType::String], &runtime.vm.output[4..]).unwrap();
+
+    assert_eq!(decoded, vec![Token::String(reason.to_string())]);
+}
+
+#[test]
+fn decode_fixed_array() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function f(uint256[3] a, uint64 b) public pure returns (uint256, uint256, uint256, uint64) {
+                return (a[0], a[1], a[2], b);
+            }
+
+            function g(bytes data) public pure returns (uint256) {
+                uint256[3] a = abi.decode(data, (uint256[3]));
+
+                return a[0] + a[1] + a[2];
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let array = Token::FixedArray(vec![
+        Token::Uint(U256::from(1)),
+        Token::Uint(U256::max_value()),
+        Token::Uint(U256::from(3)),
+    ]);
+
+    let calldata = runtime.abi.functions["f"][0]
+        .encode_input(&[array.clone(), Token::Uint(U256::from(4))])
+        .unwrap();
+
+    // the array is inline, without an offset or length
+    assert_eq!(calldata.len(), 4 + 4 * 32);
+
+    let returns = runtime.function("f", &[array.clone(), Token::Uint(U256::from(4))]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::max_value()),
+            Token::Uint(U256::from(3)),
+            Token::Uint(U256::from(4)),
+        ]
+    );
+
+    let array = Token::FixedArray(vec![
+        Token::Uint(U256::from(100)),
+        Token::Uint(U256::from(20)),
+        Token::Uint(U256::from(3)),
+    ]);
+
+    let returns = runtime.function("g", &[Token::Bytes(ethabi::encode(&[array]))]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(123))]);
+}
+
+#[test]
+fn signed_integers() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function echo(int8 a, int256 b) public pure returns (int8, int256) {
+                return (a, b);
+            }
+
+            function encode() public pure returns (bytes) {
+                return abi.encode(int8(-1), int64(-2), int256(-123456));
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // negative values are two's complement, sign extended to 256 bits
+    let minus_one = Token::Int(U256::max_value());
+    let minus_123456 = Token::Int(!U256::from(123455));
+
+    let calldata = runtime.abi.functions["echo"][0]
+        .encode_input(&[minus_one.clone(), minus_123456.clone()])
+        .unwrap();
+
+    assert!(runtime.invoke(calldata));
+
+    assert_eq!(runtime.vm.output[..32], [0xff; 32]);
+
+    let returns = runtime.function("echo", &[minus_one.clone(), minus_123456.clone()]);
+
+    assert_eq!(returns, vec![minus_one.clone(), minus_123456.clone()]);
+
+    let returns = runtime.function("encode", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Bytes(ethabi::encode(&[
+            minus_one,
+            Token::Int(!U256::from(1)),
+            minus_123456,
+        ]))]
+    );
+}
+
+#[test]
+fn constructor_abi() {
+    let ns = parse_and_resolve(
+        r##"
+        contract base {
+            constructor(uint8 level) {}
+        }
+
+        contract c is base {
+            constructor(address owner, uint64[] memory amounts, string memory name) payable base(1) {}
+
+            function f() public pure returns (uint64) {
+                return 1;
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    let abi = gen_abi(1, &ns);
+
+    // only the constructor of the contract itself is listed, not that of its base
+    let constructors: Vec<_> = abi.iter().filter(|abi| abi.ty == "constructor").collect();
+
+    assert_eq!(constructors.len(), 1);
+    assert_eq!(constructors[0].mutability, "payable");
+    assert!(constructors[0].outputs.is_none());
+
+    let inputs: Vec<(&str, &str)> = constructors[0]
+        .inputs
+        .iter()
+        .map(|param| (param.name.as_str(), param.ty.as_str()))
+        .collect();
+
+    assert_eq!(
+        inputs,
+        vec![
+            ("owner", "address"),
+            ("amounts", "uint64[]"),
+            ("name", "string")
+        ]
+    );
+
+    // deployment tooling can encode the constructor arguments from the json
+    let json = serde_json::to_string(&abi).unwrap();
+
+    let contract = ethabi::Contract::load(json.as_bytes()).unwrap();
+
+    let params: Vec<ethabi::ParamType> = contract
+        .constructor
+        .unwrap()
+        .inputs
+        .into_iter()
+        .map(|param| param.kind)
+        .collect();
+
+    assert_eq!(
+        params,
+        vec![
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(64))),
+            ethabi::ParamType::String,
+        ]
+    );
+}
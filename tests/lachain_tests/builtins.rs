@@ -0,0 +1,825 @@
+use crate::{build_llvm_ir, build_solidity, first_error, parse_and_resolve};
+use ethabi::Token;
+use ethereum_types::U256;
+use solang::codegen::{codegen, Options};
+use solang::sema::ast::Level;
+use solang::Target;
+use tiny_keccak::{Hasher, Keccak};
+
+#[test]
+fn creation_code() {
+    let mut runtime = build_solidity(
+        r##"
+        contract child {
+            function foo() public pure returns (uint32) {
+                return 102;
+            }
+        }
+
+        contract c {
+            function creation() public pure returns (uint32) {
+                return uint32(type(child).creationCode.length);
+            }
+
+            function runtime() public pure returns (uint32) {
+                return uint32(type(child).runtimeCode.length);
+            }
+
+            function first_byte() public pure returns (bytes1) {
+                return type(child).creationCode[0];
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // Lachain has no separate deployer, so creation and runtime code are the same module
+    let child_len = runtime.contracts[0].len();
+
+    let returns = runtime.function("creation", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(child_len))]);
+
+    let returns = runtime.function("runtime", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(child_len))]);
+
+    // wasm magic
+    let returns = runtime.function("first_byte", &[]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(vec![0])]);
+}
+
+#[test]
+fn interface_id_and_name() {
+    let mut runtime = build_solidity(
+        r##"
+        interface IERC165 {
+            function supportsInterface(bytes4 interfaceId) external view returns (bool);
+        }
+
+        contract token is IERC165 {
+            function supportsInterface(bytes4 interfaceId) public view override returns (bool) {
+                return interfaceId == type(IERC165).interfaceId;
+            }
+
+            function erc165_id() public pure returns (bytes4) {
+                return type(IERC165).interfaceId;
+            }
+
+            function is_erc165() public pure returns (bool) {
+                return type(IERC165).interfaceId == 0x01ffc9a7;
+            }
+
+            function name() public pure returns (string) {
+                return type(token).name;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("erc165_id", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::FixedBytes(vec![0x01, 0xff, 0xc9, 0xa7])]
+    );
+
+    let returns = runtime.function("is_erc165", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+
+    let returns = runtime.function(
+        "supportsInterface",
+        &[Token::FixedBytes(vec![0x01, 0xff, 0xc9, 0xa7])],
+    );
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+
+    let returns = runtime.function(
+        "supportsInterface",
+        &[Token::FixedBytes(vec![0xff, 0xff, 0xff, 0xff])],
+    );
+
+    assert_eq!(returns, vec![Token::Bool(false)]);
+
+    let returns = runtime.function("name", &[]);
+
+    assert_eq!(returns, vec![Token::String(String::from("token"))]);
+}
+
+#[test]
+fn address_code_empty() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function zero() public view returns (uint32) {
+                return uint32(address(0).code.length);
+            }
+
+            function other(address a) public view returns (bytes) {
+                return a.code;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("zero", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(0))]);
+
+    let returns = runtime.function(
+        "other",
+        &[Token::Address(ethereum_types::Address::from([0x55; 20]))],
+    );
+
+    assert_eq!(returns, vec![Token::Bytes(vec![])]);
+}
+
+#[test]
+fn blobs_are_zero() {
+    let src = r##"
+        contract c {
+            function hash(uint256 index) public view returns (bytes32) {
+                return blobhash(index);
+            }
+
+            function fee() public view returns (uint256) {
+                return block.blobbasefee + block.blobbasefee;
+            }
+
+            function both() public view returns (bool) {
+                return blobhash(0) == 0 && blobhash(1) == 0;
+            }
+        }"##;
+
+    let ns = parse_and_resolve(src, Target::Lachain);
+
+    let warnings = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning)
+        .map(|d| d.message.as_str())
+        .collect::<Vec<&str>>();
+
+    // one warning per builtin, however often it is used
+    assert_eq!(
+        warnings,
+        vec![
+            "‘blobhash’ not supported on target LACHAIN, it always evaluates to zero",
+            "‘block.blobbasefee’ not supported on target LACHAIN, it always evaluates to zero",
+        ]
+    );
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("hash", &[Token::Uint(U256::from(3))]);
+
+    assert_eq!(returns, vec![Token::FixedBytes(vec![0u8; 32])]);
+
+    let returns = runtime.function("fee", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::zero())]);
+
+    let returns = runtime.function("both", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+}
+
+#[test]
+fn interface_id_erc20() {
+    let mut runtime = build_solidity(
+        r##"
+        interface IERC20 {
+            event Transfer(address indexed from, address indexed to, uint256 value);
+            event Approval(address indexed owner, address indexed spender, uint256 value);
+
+            function totalSupply() external view returns (uint256);
+            function balanceOf(address account) external view returns (uint256);
+            function transfer(address recipient, uint256 amount) external returns (bool);
+            function allowance(address owner, address spender) external view returns (uint256);
+            function approve(address spender, uint256 amount) external returns (bool);
+            function transferFrom(address sender, address recipient, uint256 amount) external returns (bool);
+        }
+
+        contract c {
+            function id() public pure returns (bytes4) {
+                return type(IERC20).interfaceId;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("id", &[]);
+
+    // the well-known ERC-20 interface id; events do not contribute to it
+    assert_eq!(
+        returns,
+        vec![Token::FixedBytes(vec![0x36, 0x37, 0x2b, 0x07])]
+    );
+}
+
+#[test]
+fn function_selector() {
+    let src = r##"
+        interface I {
+            function bar(address to, uint256 v) external returns (bool);
+        }
+
+        contract c {
+            function foo(uint64 a, string memory s) public {}
+
+            function this_selector() public view returns (bytes4) {
+                return this.foo.selector;
+            }
+
+            function own_selector() public pure returns (bytes4) {
+                return c.foo.selector;
+            }
+
+            function other_selector() public pure returns (bytes4) {
+                return I.bar.selector;
+            }
+        }"##;
+
+    fn selector(signature: &str) -> Token {
+        let mut hasher = Keccak::v256();
+        let mut hash = [0u8; 32];
+        hasher.update(signature.as_bytes());
+        hasher.finalize(&mut hash);
+
+        Token::FixedBytes(hash[..4].to_vec())
+    }
+
+    // the selectors are constant folded, so no FunctionSelector builtin is left
+    let mut ns = parse_and_resolve(src, Target::Lachain);
+
+    codegen(&mut ns, &Options::default());
+
+    let contract = &ns.contracts[1];
+
+    for cfg in &contract.cfg {
+        assert!(!cfg.to_string(contract, &ns).contains("FunctionSelector"));
+    }
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("this_selector", &[]);
+
+    assert_eq!(returns, vec![selector("foo(uint64,string)")]);
+
+    let returns = runtime.function("own_selector", &[]);
+
+    assert_eq!(returns, vec![selector("foo(uint64,string)")]);
+
+    let returns = runtime.function("other_selector", &[]);
+
+    assert_eq!(returns, vec![selector("bar(address,uint256)")]);
+}
+
+#[test]
+fn eip712_digest() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            bytes32 constant DOMAIN_TYPEHASH = keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+            bytes32 constant MAIL_TYPEHASH = keccak256("Mail(address from,address to,uint256 amount)");
+
+            function digest(uint256 chainId, address verifyingContract, address from, address to, uint256 amount) public pure returns (bytes32) {
+                bytes32 domainSeparator = keccak256(abi.encode(
+                    DOMAIN_TYPEHASH,
+                    keccak256(bytes("Ether Mail")),
+                    keccak256(bytes("1")),
+                    chainId,
+                    verifyingContract
+                ));
+
+                bytes32 structHash = keccak256(abi.encode(MAIL_TYPEHASH, from, to, amount));
+
+                return keccak256(abi.encodePacked("\x19\x01", domainSeparator, structHash));
+            }
+        }"##,
+    );
+
+    fn keccak256(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Keccak::v256();
+        let mut hash = [0u8; 32];
+        hasher.update(data);
+        hasher.finalize(&mut hash);
+
+        hash.to_vec()
+    }
+
+    runtime.constructor(&[]);
+
+    let chain_id = Token::Uint(U256::from(225));
+    let verifying_contract = Token::Address(ethabi::Address::from([0xcc; 20]));
+    let from = Token::Address(ethabi::Address::from([0xcd; 20]));
+    let to = Token::Address(ethabi::Address::from([0xbb; 20]));
+    let amount = Token::Uint(U256::from(1_000_000u64));
+
+    let returns = runtime.function(
+        "digest",
+        &[
+            chain_id.clone(),
+            verifying_contract.clone(),
+            from.clone(),
+            to.clone(),
+            amount.clone(),
+        ],
+    );
+
+    // compute the same digest off-chain
+    let domain_separator = keccak256(&ethabi::encode(&[
+        Token::FixedBytes(keccak256(
+            b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+        )),
+        Token::FixedBytes(keccak256(b"Ether Mail")),
+        Token::FixedBytes(keccak256(b"1")),
+        chain_id,
+        verifying_contract,
+    ]));
+
+    let struct_hash = keccak256(&ethabi::encode(&[
+        Token::FixedBytes(keccak256(b"Mail(address from,address to,uint256 amount)")),
+        from,
+        to,
+        amount,
+    ]));
+
+    let mut message = vec![0x19, 0x01];
+    message.extend(domain_separator);
+    message.extend(struct_hash);
+
+    assert_eq!(returns, vec![Token::FixedBytes(keccak256(&message))]);
+}
+
+#[test]
+fn eip712_ecrecover() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            bytes32 constant DOMAIN_TYPEHASH = keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+            bytes32 constant MAIL_TYPEHASH = keccak256("Mail(address to,uint256 amount)");
+
+            function digest(address to, uint256 amount) public pure returns (bytes32) {
+                bytes32 domainSeparator = keccak256(abi.encode(
+                    DOMAIN_TYPEHASH,
+                    keccak256(bytes("Ether Mail")),
+                    keccak256(bytes("1")),
+                    uint256(225),
+                    address(0)
+                ));
+
+                bytes32 structHash = keccak256(abi.encode(MAIL_TYPEHASH, to, amount));
+
+                return keccak256(abi.encodePacked("\x19\x01", domainSeparator, structHash));
+            }
+
+            function signer(address to, uint256 amount, uint8 v, bytes32 r, bytes32 s) public pure returns (address) {
+                return ecrecover(digest(to, amount), v, r, s);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let to = Token::Address(ethabi::Address::from([0xbb; 20]));
+    let amount = Token::Uint(U256::from(1_000_000u64));
+
+    let returns = runtime.function("digest", &[to.clone(), amount.clone()]);
+
+    let digest = match &returns[0] {
+        Token::FixedBytes(digest) => digest.clone(),
+        _ => unreachable!(),
+    };
+
+    let secret_key = libsecp256k1::SecretKey::parse(&[0x42; 32]).unwrap();
+    let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+    hasher.update(&public_key.serialize()[1..]);
+    hasher.finalize(&mut hash);
+
+    let expected = ethabi::Address::from_slice(&hash[12..]);
+
+    let mut message = [0u8; 32];
+    message.copy_from_slice(&digest);
+
+    let (signature, recovery_id) =
+        libsecp256k1::sign(&libsecp256k1::Message::parse(&message), &secret_key);
+
+    let signature = signature.serialize();
+
+    let v = Token::Uint(U256::from(27 + recovery_id.serialize()));
+    let r = Token::FixedBytes(signature[..32].to_vec());
+    let s = Token::FixedBytes(signature[32..].to_vec());
+
+    let returns = runtime.function(
+        "signer",
+        &[to.clone(), amount.clone(), v.clone(), r.clone(), s.clone()],
+    );
+
+    assert_eq!(returns, vec![Token::Address(expected)]);
+
+    // a different message recovers a different address
+    let returns = runtime.function(
+        "signer",
+        &[
+            to.clone(),
+            Token::Uint(U256::from(1_000_001u64)),
+            v,
+            r.clone(),
+            s.clone(),
+        ],
+    );
+
+    assert_ne!(returns, vec![Token::Address(expected)]);
+
+    // an invalid v gives the zero address
+    let returns = runtime.function("signer", &[to, amount, Token::Uint(U256::from(29)), r, s]);
+
+    assert_eq!(returns, vec![Token::Address(ethabi::Address::zero())]);
+}
+
+#[test]
+fn chain_id() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function id() public view returns (uint256) {
+                return block.chainid;
+            }
+
+            function id_shifted() public view returns (uint256) {
+                return block.chainid << 192;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("id", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(225))]);
+
+    // the upper bits are zero, not whatever was on the stack
+    let returns = runtime.function("id_shifted", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(225) << 192)]);
+}
+
+#[test]
+fn base_fee() {
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function fee() public view returns (uint256) {
+                return block.basefee;
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "‘block.basefee’ is not available, since the Lachain host does not provide the base fee"
+    );
+}
+
+#[test]
+fn ecrecover_known_signer() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function recover(bytes32 hash, uint8 v, bytes32 r, bytes32 s) public pure returns (address) {
+                return ecrecover(hash, v, r, s);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // a well known test key and its address
+    let secret_key = libsecp256k1::SecretKey::parse_slice(
+        &hex::decode("4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap(),
+    )
+    .unwrap();
+
+    let signer = ethabi::Address::from_slice(
+        &hex::decode("2c7536e3605d9c16a7a3d7b1898e529396a65c23").unwrap(),
+    );
+
+    let mut hash = [0u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(b"Some data");
+    hasher.finalize(&mut hash);
+
+    let (signature, recovery_id) =
+        libsecp256k1::sign(&libsecp256k1::Message::parse(&hash), &secret_key);
+
+    let signature = signature.serialize();
+
+    let returns = runtime.function(
+        "recover",
+        &[
+            Token::FixedBytes(hash.to_vec()),
+            Token::Uint(U256::from(27 + recovery_id.serialize())),
+            Token::FixedBytes(signature[..32].to_vec()),
+            Token::FixedBytes(signature[32..].to_vec()),
+        ],
+    );
+
+    assert_eq!(returns, vec![Token::Address(signer)]);
+}
+
+#[test]
+fn tx_origin() {
+    let mut runtime = build_solidity(
+        r##"
+        contract inner {
+            function origin() public view returns (address) {
+                return tx.origin;
+            }
+
+            function sender() public view returns (address) {
+                return msg.sender;
+            }
+        }
+
+        contract c {
+            address owner;
+            inner i;
+
+            constructor() {
+                owner = tx.origin;
+                i = new inner();
+            }
+
+            function origin() public view returns (address) {
+                return tx.origin;
+            }
+
+            function nested() public view returns (address, address) {
+                return (i.origin(), i.sender());
+            }
+
+            function only_owner() public view returns (bool) {
+                require(tx.origin == owner, "not owner");
+
+                return true;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let origin = Token::Address(ethereum_types::Address::from(runtime.vm.caller));
+
+    let returns = runtime.function("origin", &[]);
+
+    assert_eq!(returns, vec![origin.clone()]);
+
+    // through another contract, the sender changes but the origin does not
+    let returns = runtime.function("nested", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            origin.clone(),
+            Token::Address(ethereum_types::Address::from(runtime.vm.cur))
+        ]
+    );
+
+    let returns = runtime.function("only_owner", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+}
+
+#[test]
+fn gasleft_accumulator() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint256 spent;
+
+            function account(uint32 n) public returns (uint256) {
+                // start beyond 64 bits, so any truncation to the width of gasleft shows
+                spent = 2**64;
+
+                for (uint32 i = 0; i < n; i++) {
+                    spent += gasleft();
+                }
+
+                return spent;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("account", &[Token::Uint(U256::from(3))]);
+
+    // the mock host always reports 2224000 gas left
+    assert_eq!(
+        returns,
+        vec![Token::Uint(
+            (U256::from(1) << 64) + U256::from(3 * 2_224_000u64)
+        )]
+    );
+}
+
+#[test]
+fn own_code_length() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function size() public view returns (uint32) {
+                return uint32(address(this).code.length);
+            }
+
+            function code() public view returns (bytes) {
+                return address(this).code;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let deployed = runtime.accounts[&runtime.vm.cur].0.clone();
+
+    assert!(!deployed.is_empty());
+
+    let returns = runtime.function("size", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(deployed.len()))]);
+
+    let returns = runtime.function("code", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(deployed)]);
+
+    // the length is read from the host without copying the code
+    let ir = build_llvm_ir(
+        r##"
+        contract c {
+            function size() public view returns (uint32) {
+                return uint32(address(this).code.length);
+            }
+        }"##,
+        &Options::default(),
+    )
+    .unoptimized;
+
+    let start = ir.find("@\"c::function::size\"(").unwrap();
+    let end = start + ir[start..].find("\n}\n").unwrap();
+    let size = &ir[start..end];
+
+    assert!(size.contains("@get_external_code_size("));
+    assert!(!size.contains("@copy_external_code("));
+}
+
+#[test]
+fn gasleft_uint256() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function double() public view returns (uint256) {
+                return gasleft() * 2;
+            }
+
+            function shifted() public view returns (uint256) {
+                // would overflow if gasleft() was 64 bits wide
+                return gasleft() << 100;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("double", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(2 * 2_224_000u64))]);
+
+    let returns = runtime.function("shifted", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(2_224_000u64) << 100)]);
+}
+
+#[test]
+fn assembly_invalid() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function f(bool halt) public pure returns (uint32) {
+                if (halt) {
+                    assembly {
+                        invalid()
+                    }
+                }
+
+                return 7;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("f", &[Token::Bool(false)]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(7))]);
+
+    let calldata = runtime.abi.functions["f"][0]
+        .encode_input(&[Token::Bool(true)])
+        .unwrap();
+
+    // halts with a non-zero code and no return data
+    assert!(!runtime.invoke(calldata));
+    assert!(runtime.vm.output.is_empty());
+
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function f() public {
+                assembly {
+                    invalid()
+                    stop()
+                }
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "evm assembly not supported on target LACHAIN"
+    );
+}
+
+#[test]
+fn block_number_uint256() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint256 deadline = 950_000;
+
+            function later() public view returns (uint256) {
+                return block.number + 100;
+            }
+
+            function expired() public view returns (bool) {
+                return block.number > deadline;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("later", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(950_377))]);
+
+    let returns = runtime.function("expired", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+}
+
+#[test]
+fn timestamp_uint256() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function tomorrow() public view returns (uint256) {
+                return block.timestamp + 1 days;
+            }
+
+            function shifted() public view returns (uint256) {
+                // would be truncated if block.timestamp was 64 bits wide
+                return (block.timestamp + 1 days) << 200;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("tomorrow", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(1_594_035_638u64 + 86_400))]
+    );
+
+    let returns = runtime.function("shifted", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(1_594_035_638u64 + 86_400) << 200)]
+    );
+}
@@ -0,0 +1,509 @@
+use crate::{address_new, build_solidity, build_solidity_with_options};
+use ethabi::Token;
+use solang::codegen::Options;
+
+#[test]
+fn call_to_eoa_reverts() {
+    let mut runtime = build_solidity(
+        r##"
+        interface I {
+            function f() external returns (uint64);
+        }
+
+        contract c {
+            function call_eoa(address a) public returns (uint64) {
+                return I(a).f();
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let calldata = runtime.abi.functions["call_eoa"][0]
+        .encode_input(&[Token::Address(ethabi::Address::from(address_new()))])
+        .unwrap();
+
+    assert!(!runtime.invoke(calldata));
+}
+
+#[test]
+fn try_call_to_eoa() {
+    let mut runtime = build_solidity(
+        r##"
+        interface I {
+            function f() external returns (uint64);
+        }
+
+        contract callee {
+            function f() public pure returns (uint64) {
+                return 7;
+            }
+        }
+
+        contract c {
+            function try_eoa(address a) public returns (uint64, uint32) {
+                try I(a).f() returns (uint64 v) {
+                    return (v, 0);
+                } catch (bytes memory reason) {
+                    return (1000, uint32(reason.length));
+                }
+            }
+
+            function try_callee() public returns (uint64, uint32) {
+                callee a = new callee();
+
+                try I(address(a)).f() returns (uint64 v) {
+                    return (v, 0);
+                } catch (bytes memory reason) {
+                    return (1000, uint32(reason.length));
+                }
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // the catch clause runs with an empty reason
+    let returns = runtime.function(
+        "try_eoa",
+        &[Token::Address(ethabi::Address::from(address_new()))],
+    );
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethabi::Uint::from(1000)),
+            Token::Uint(ethabi::Uint::from(0))
+        ]
+    );
+
+    let returns = runtime.function("try_callee", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethabi::Uint::from(7)),
+            Token::Uint(ethabi::Uint::from(0))
+        ]
+    );
+}
+
+#[test]
+fn low_level_call_to_eoa() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function call_eoa(address a) public returns (bool, uint32) {
+                (bool ok, bytes memory data) = a.call(hex"1222");
+
+                return (ok, uint32(data.length));
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function(
+        "call_eoa",
+        &[Token::Address(ethabi::Address::from(address_new()))],
+    );
+
+    assert_eq!(
+        returns,
+        vec![Token::Bool(true), Token::Uint(ethabi::Uint::from(0))]
+    );
+}
+
+#[test]
+fn low_level_call_reverted() {
+    let mut runtime = build_solidity(
+        r##"
+        contract callee {
+            function f() public pure {
+                revert("boom");
+            }
+        }
+
+        contract c {
+            function call_f() public returns (bool, bytes) {
+                callee a = new callee();
+
+                (bool ok, bytes memory d) = address(a).call(abi.encodeWithSignature("f()"));
+
+                return (ok, d);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("call_f", &[]);
+
+    // the return data is the Error(string) revert reason
+    let mut reason = hex::decode("08c379a0").unwrap();
+    reason.extend(ethabi::encode(&[Token::String(String::from("boom"))]));
+
+    assert_eq!(returns, vec![Token::Bool(false), Token::Bytes(reason)]);
+}
+
+#[test]
+fn max_return_data() {
+    let src = r##"
+        contract callee {
+            function big() public pure returns (bytes) {
+                return new bytes(4096);
+            }
+        }
+
+        contract c {
+            function call_big() public returns (bool, uint32) {
+                callee a = new callee();
+
+                (bool ok, bytes memory d) = address(a).call(abi.encodeWithSignature("big()"));
+
+                return (ok, uint32(d.length));
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("call_big", &[]);
+
+    // offset, length and the bytes themselves
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bool(true),
+            Token::Uint(ethabi::Uint::from(64 + 4096))
+        ]
+    );
+
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            max_return_data: Some(1024),
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("call_big", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Bool(true), Token::Uint(ethabi::Uint::from(1024))]
+    );
+}
+
+#[test]
+fn max_revert_data() {
+    // a reverting callee cannot make the caller copy a huge revert buffer
+    let src = r##"
+        contract callee {
+            function big() public pure {
+                bytes memory b = new bytes(4096);
+
+                revert(string(b));
+            }
+
+            function ok() public pure returns (bytes) {
+                return new bytes(4096);
+            }
+        }
+
+        contract c {
+            function call_big() public returns (bool, uint32) {
+                callee a = new callee();
+
+                (bool ok, bytes memory d) = address(a).call(abi.encodeWithSignature("big()"));
+
+                return (ok, uint32(d.length));
+            }
+
+            function call_ok() public returns (bool, uint32) {
+                callee a = new callee();
+
+                (bool ok, bytes memory d) = address(a).call(abi.encodeWithSignature("ok()"));
+
+                return (ok, uint32(d.length));
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("call_big", &[]);
+
+    // Error(string) selector, offset, length and the reason itself
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bool(false),
+            Token::Uint(ethabi::Uint::from(4 + 64 + 4096))
+        ]
+    );
+
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            max_revert_data: Some(256),
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("call_big", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Bool(false), Token::Uint(ethabi::Uint::from(256))]
+    );
+
+    // the revert data cap does not apply to the return data of a successful call
+    let returns = runtime.function("call_ok", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bool(true),
+            Token::Uint(ethabi::Uint::from(64 + 4096))
+        ]
+    );
+
+    // the return data cap applies to revert data too
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            max_return_data: Some(128),
+            max_revert_data: Some(256),
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("call_big", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Bool(false), Token::Uint(ethabi::Uint::from(128))]
+    );
+}
+
+#[test]
+fn large_array_return() {
+    // the return data is copied with a single copy_return_value call and decoded in place;
+    // the abi offsets can point anywhere so it cannot be decoded word by word
+    let mut runtime = build_solidity(
+        r##"
+        contract callee {
+            function squares(uint32 n) public pure returns (uint256[]) {
+                uint256[] memory a = new uint256[](n);
+
+                for (uint32 i = 0; i < n; i++) {
+                    a[i] = uint256(i) * i;
+                }
+
+                return a;
+            }
+        }
+
+        contract c {
+            function sum(uint32 n) public returns (uint256 total, uint32 len) {
+                callee a = new callee();
+
+                uint256[] memory squares = a.squares(n);
+
+                len = uint32(squares.length);
+
+                for (uint32 i = 0; i < len; i++) {
+                    total += squares[i];
+                }
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("sum", &[Token::Uint(ethabi::Uint::from(1000))]);
+
+    let total: u64 = (0..1000u64).map(|i| i * i).sum();
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethabi::Uint::from(total)),
+            Token::Uint(ethabi::Uint::from(1000))
+        ]
+    );
+}
+
+#[test]
+fn decode_string_return() {
+    let mut runtime = build_solidity(
+        r##"
+        contract callee {
+            function name(uint32 n) public pure returns (string) {
+                if (n == 0) {
+                    return "";
+                }
+
+                return "a name which is longer than thirty two bytes";
+            }
+        }
+
+        contract c {
+            function typed(uint32 n) public returns (string) {
+                callee a = new callee();
+
+                return a.name(n);
+            }
+
+            function low_level(uint32 n) public returns (string) {
+                callee a = new callee();
+
+                (bool ok, bytes memory d) = address(a).call(abi.encodeWithSignature("name(uint32)", n));
+
+                require(ok);
+
+                return abi.decode(d, (string));
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    for f in &["typed", "low_level"] {
+        let returns = runtime.function(f, &[Token::Uint(ethabi::Uint::from(1))]);
+
+        assert_eq!(
+            returns,
+            vec![Token::String(String::from(
+                "a name which is longer than thirty two bytes"
+            ))]
+        );
+
+        let returns = runtime.function(f, &[Token::Uint(ethabi::Uint::from(0))]);
+
+        assert_eq!(returns, vec![Token::String(String::new())]);
+    }
+}
+
+#[test]
+fn this_call_sender() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function external_self() public returns (bool) {
+                return this.is_self();
+            }
+
+            function internal_self() public view returns (bool) {
+                return is_self();
+            }
+
+            function is_self() public view returns (bool) {
+                return msg.sender == address(this);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // this.is_self() is an external call from the contract to itself
+    let returns = runtime.function("external_self", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+
+    // a plain is_self() is an internal call which keeps the original sender
+    let returns = runtime.function("internal_self", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(false)]);
+}
+
+#[test]
+fn proxy_forwards_calldata() {
+    let mut runtime = build_solidity(
+        r##"
+        contract implementation {
+            function add(uint64 a, uint64 b) public pure returns (uint64) {
+                return a + b;
+            }
+        }
+
+        contract proxy {
+            implementation imp;
+
+            constructor() {
+                imp = new implementation();
+            }
+
+            function add(uint64, uint64) public returns (uint64) {
+                (bool ok, bytes memory r) = address(imp).delegatecall(msg.data);
+
+                require(ok);
+
+                return abi.decode(r, (uint64));
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function(
+        "add",
+        &[
+            Token::Uint(ethabi::Uint::from(40)),
+            Token::Uint(ethabi::Uint::from(2)),
+        ],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(42))]);
+}
+
+#[test]
+fn delegatecall_storage() {
+    let mut runtime = build_solidity(
+        r##"
+        contract logic {
+            address imp;
+            uint64 counter;
+
+            function bump(uint64 n) public {
+                counter += n;
+            }
+        }
+
+        contract c {
+            address imp;
+            uint64 counter;
+
+            constructor() {
+                imp = address(new logic());
+            }
+
+            function bump(uint64 n) public returns (uint64) {
+                (bool ok, ) = imp.delegatecall(abi.encodeWithSignature("bump(uint64)", n));
+
+                require(ok);
+
+                return counter;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("bump", &[Token::Uint(ethabi::Uint::from(5))]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(5))]);
+
+    let returns = runtime.function("bump", &[Token::Uint(ethabi::Uint::from(7))]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(12))]);
+}
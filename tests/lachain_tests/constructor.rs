@@ -0,0 +1,258 @@
+use crate::build_solidity;
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn constructor_args() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint256 x;
+            uint64 y = 7;
+
+            constructor(uint256 _x) {
+                x = _x;
+                y += 1;
+            }
+
+            function get() public view returns (uint256, uint64) {
+                return (x, y);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[Token::Uint(U256::from(0xdead_beef_u64))]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(0xdead_beef_u64)),
+            Token::Uint(U256::from(8))
+        ]
+    );
+}
+
+#[test]
+fn create_with_args() {
+    let mut runtime = build_solidity(
+        r##"
+        contract child {
+            uint64 value;
+
+            constructor(uint64 v) {
+                value = v * 2;
+            }
+
+            function get() public view returns (uint64) {
+                return value;
+            }
+        }
+
+        contract creator {
+            function make(uint64 v) public returns (uint64) {
+                child c = new child(v);
+
+                return c.get();
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("make", &[Token::Uint(U256::from(21))]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(42))]);
+}
+
+#[test]
+fn immutables() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            address immutable owner = msg.sender;
+            uint64 immutable a;
+            uint64 immutable b;
+
+            constructor(uint64 x) {
+                a = x;
+                b = a + 1;
+            }
+
+            function get() public view returns (address, uint64, uint64) {
+                return (owner, a, b);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[Token::Uint(U256::from(41))]);
+
+    // immutables and their tag are appended to the code rather than kept in storage
+    assert!(runtime.store.is_empty());
+    assert_eq!(
+        runtime.accounts[&runtime.vm.cur].0.len(),
+        runtime.contracts.last().unwrap().len() + 4 * 32
+    );
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Address(ethabi::Address::from(runtime.vm.caller)),
+            Token::Uint(U256::from(41)),
+            Token::Uint(U256::from(42)),
+        ]
+    );
+}
+
+#[test]
+fn constructor_only() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 x;
+
+            constructor(uint64 a) {
+                x = a * 2;
+            }
+        }"##,
+    );
+
+    let code = runtime.contracts.last().unwrap().clone();
+
+    // the module still exports both entrypoints
+    let module = parity_wasm::deserialize_buffer::<parity_wasm::elements::Module>(&code).unwrap();
+
+    let exports: Vec<&str> = module
+        .export_section()
+        .unwrap()
+        .entries()
+        .iter()
+        .map(|e| e.field())
+        .collect();
+
+    assert!(exports.contains(&"start"));
+    assert!(exports.contains(&"deploy"));
+
+    runtime.constructor(&[Token::Uint(U256::from(21))]);
+
+    assert_eq!(runtime.store.len(), 1);
+
+    // there is nothing to call, so any call reverts
+    assert!(!runtime.invoke(vec![0xde, 0xad, 0xbe, 0xef]));
+    assert!(!runtime.invoke(Vec::new()));
+}
+
+#[test]
+fn constructor_require() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 value;
+
+            constructor(uint64 arg) {
+                require(arg > 0, "arg must be positive");
+
+                value = arg;
+            }
+
+            function get() public view returns (uint64) {
+                return value;
+            }
+        }"##,
+    );
+
+    runtime.constructor_expect_revert(&[Token::Uint(U256::from(0))]);
+
+    // Error(string) with the reason
+    assert_eq!(&runtime.vm.output[..4], &[0x08, 0xc3, 0x79, 0xa0]);
+
+    let reason = ethabi::decode(&[ethabi::ParamType::String], &runtime.vm.output[4..]).unwrap();
+
+    assert_eq!(
+        reason,
+        vec![Token::String(String::from("arg must be positive"))]
+    );
+
+    assert!(!runtime.accounts.contains_key(&runtime.vm.cur));
+
+    runtime.constructor(&[Token::Uint(U256::from(5))]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(5))]);
+}
+
+#[test]
+fn dependent_immutables() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 immutable a = 41;
+            uint64 immutable b = a + 1;
+            uint64 immutable c;
+
+            constructor(uint64 x) {
+                c = b * x;
+            }
+
+            function get() public view returns (uint64, uint64, uint64) {
+                return (a, b, c);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[Token::Uint(U256::from(2))]);
+
+    // b and c read the immutables written before them during construction, which
+    // never touches storage
+    assert!(runtime.store.is_empty());
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(41)),
+            Token::Uint(U256::from(42)),
+            Token::Uint(U256::from(84)),
+        ]
+    );
+}
+
+#[test]
+fn immutables_delegatecall() {
+    let mut runtime = build_solidity(
+        r##"
+        contract logic {
+            uint64 immutable a = 102;
+
+            function get() public view returns (uint64) {
+                return a;
+            }
+        }
+
+        contract c {
+            address imp;
+
+            constructor() {
+                imp = address(new logic());
+            }
+
+            function get() public returns (bool) {
+                (bool ok, ) = imp.delegatecall(abi.encodeWithSignature("get()"));
+
+                return ok;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // the immutables at the end of our code are not those of logic, so the call must fail
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Bool(false)]);
+}
@@ -0,0 +1,367 @@
+use crate::build_llvm_ir;
+use inkwell::attributes::{Attribute, AttributeLoc};
+use inkwell::context::Context;
+use inkwell::memory_buffer::MemoryBuffer;
+use solang::codegen::Options;
+
+#[test]
+fn emit_llvm_ir() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let src = dir.path().join("test.sol");
+
+    std::fs::write(
+        &src,
+        r##"
+        contract c {
+            uint64 count;
+
+            function inc() public {
+                count += 1;
+            }
+        }"##,
+    )
+    .unwrap();
+
+    assert_cmd::Command::cargo_bin("solang")
+        .unwrap()
+        .args(&["--target", "lachain", "--emit", "llvm-ir", "--output"])
+        .arg(dir.path())
+        .args(&["-O", "aggressive"])
+        .arg(&src)
+        .assert()
+        .success();
+
+    let context = Context::create();
+
+    // the IR before and after the optimization passes
+    for name in &["c.ll", "c.opt.ll"] {
+        let module = context
+            .create_module_from_ir(MemoryBuffer::create_from_file(&dir.path().join(name)).unwrap())
+            .unwrap();
+
+        assert!(module.get_function("start").is_some());
+        assert!(module.get_function("deploy").is_some());
+    }
+
+    let unoptimized = std::fs::read_to_string(dir.path().join("c.ll")).unwrap();
+    let optimized = std::fs::read_to_string(dir.path().join("c.opt.ll")).unwrap();
+
+    assert_ne!(unoptimized, optimized);
+}
+
+#[test]
+fn unused_functions_removed() {
+    let src = r##"
+        contract c {
+            uint64 count;
+
+            constructor() {
+                count = 102;
+            }
+
+            function inc() public {
+                count = used_helper(count);
+            }
+
+            function used_helper(uint64 v) internal pure returns (uint64) {
+                return v + 1;
+            }
+
+            function unused_helper(uint64 v) internal pure returns (uint64) {
+                return v * 7;
+            }
+        }"##;
+
+    // in debug builds, the optimization passes are followed by a check that every function
+    // left in the module is reachable
+    let ir = build_llvm_ir(src, &Options::default());
+
+    assert!(ir.unoptimized.contains("unused_helper"));
+    assert!(!ir.optimized.contains("unused_helper"));
+
+    let runtime = crate::build_solidity(src);
+
+    let module = parity_wasm::deserialize_buffer::<parity_wasm::elements::Module>(
+        runtime.contracts.last().unwrap(),
+    )
+    .unwrap();
+
+    let exports: Vec<&str> = module
+        .export_section()
+        .unwrap()
+        .entries()
+        .iter()
+        .map(|export| export.field())
+        .collect();
+
+    assert!(exports.contains(&"start"));
+    assert!(exports.contains(&"deploy"));
+    assert!(!exports.iter().any(|name| name.contains("unused_helper")));
+}
+
+#[test]
+fn delete_does_not_load() {
+    let ir = build_llvm_ir(
+        r##"
+        contract c {
+            uint8 small;
+
+            function set(uint8 v) public {
+                small = v;
+            }
+
+            function clear() public {
+                delete small;
+            }
+        }"##,
+        &Options::default(),
+    )
+    .unoptimized;
+
+    // find the body of the clear function
+    let start = ir.find("@\"c::function::clear\"(").unwrap();
+    let end = start + ir[start..].find("\n}\n").unwrap();
+    let clear = &ir[start..end];
+
+    assert!(clear.contains("@save_storage("));
+    assert!(!clear.contains("@load_storage("));
+}
+
+#[test]
+fn meter_gas() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let src = dir.path().join("test.sol");
+
+    std::fs::write(
+        &src,
+        r##"
+        contract c {
+            uint64 count;
+
+            function inc(uint64 n) public {
+                for (uint64 i = 0; i < n; i++) {
+                    count += 1;
+                }
+            }
+        }"##,
+    )
+    .unwrap();
+
+    let compile = |meter_gas: bool| {
+        let out = tempfile::tempdir().unwrap();
+
+        let mut cmd = assert_cmd::Command::cargo_bin("solang").unwrap();
+
+        cmd.args(&["--target", "lachain", "--emit", "llvm-ir", "--output"])
+            .arg(out.path());
+
+        if meter_gas {
+            cmd.arg("--meter-gas");
+        }
+
+        cmd.arg(&src).assert().success();
+
+        let ir = std::fs::read_to_string(out.path().join("c.ll")).unwrap();
+
+        (out, ir)
+    };
+
+    let (out, ir) = compile(false);
+
+    assert!(!ir.contains("call void @meter_gas"));
+    assert!(!out.path().join("c.gas.json").exists());
+
+    let (out, ir) = compile(true);
+
+    assert!(ir.contains("call void @meter_gas(i32 0)"));
+
+    // the table is written to the output directory, not next to the source
+    assert!(!dir.path().join("test_c.gas.json").exists());
+
+    let table: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out.path().join("c.gas.json")).unwrap())
+            .unwrap();
+
+    let table = table.as_array().unwrap();
+
+    // the loop in inc() has several basic blocks
+    let inc_blocks: Vec<&serde_json::Value> = table
+        .iter()
+        .filter(|entry| entry["function"] == "c::function::inc__uint64")
+        .collect();
+
+    assert!(inc_blocks.len() > 1);
+
+    for (id, entry) in table.iter().enumerate() {
+        assert_eq!(entry["id"], id);
+        assert!(ir.contains(&format!("call void @meter_gas(i32 {})", id)));
+    }
+
+    assert!(inc_blocks[0]["location"]
+        .as_str()
+        .unwrap()
+        .contains("test.sol:5:17"));
+}
+
+#[test]
+fn always_reverts() {
+    let ir = build_llvm_ir(
+        r##"
+        contract c {
+            function fail(uint64 a) public pure returns (uint64) {
+                revert("always");
+            }
+        }"##,
+        &Options::default(),
+    )
+    .optimized;
+
+    // system_halt is noreturn, so the optimizer drops everything after it, including the
+    // return path of fail() in the dispatcher
+    let lines: Vec<&str> = ir.lines().map(|line| line.trim()).collect();
+
+    let mut halts = 0;
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("call void @system_halt(")
+            || line.starts_with("tail call void @system_halt(")
+        {
+            assert_eq!(lines[i + 1], "unreachable");
+            halts += 1;
+        }
+    }
+
+    assert!(halts > 0);
+
+    let mut runtime = crate::build_solidity(
+        r##"
+        contract c {
+            function fail(uint64 a) public pure returns (uint64) {
+                revert("always");
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    assert!(!runtime.invoke(
+        runtime.abi.functions["fail"][0]
+            .encode_input(&[ethabi::Token::Uint(1.into())])
+            .unwrap()
+    ));
+}
+
+#[test]
+fn inline_hints() {
+    let ir = build_llvm_ir(
+        r##"
+        contract c {
+            function a(uint64 x) public pure returns (uint64) {
+                return tiny(x) + big(x) + big(x + 1);
+            }
+
+            function b(uint64 x) public pure returns (uint64) {
+                return tiny(x * 2) + ping(x);
+            }
+
+            function ping(uint64 x) internal pure returns (uint64) {
+                return x == 0 ? 0 : pong(x - 1);
+            }
+
+            function pong(uint64 x) internal pure returns (uint64) {
+                return x == 0 ? 1 : ping(x - 1);
+            }
+
+            function tiny(uint64 x) internal pure returns (uint64) {
+                return x + 1;
+            }
+
+            function big(uint64 x) internal pure returns (uint64) {
+                uint64 r = 0;
+                for (uint64 i = 0; i < x; i++) {
+                    r += i * x;
+                }
+                return r;
+            }
+        }"##,
+        &Options::default(),
+    );
+
+    let context = Context::create();
+
+    let module = context
+        .create_module_from_ir(MemoryBuffer::create_from_memory_range_copy(
+            ir.unoptimized.as_bytes(),
+            "test",
+        ))
+        .unwrap();
+
+    let alwaysinline = Attribute::get_named_enum_kind_id("alwaysinline");
+
+    let inlined = |name: &str| {
+        let mut function = module.get_first_function();
+
+        while let Some(f) = function {
+            let fname = f.get_name().to_string_lossy();
+
+            if fname.contains(name) {
+                return f
+                    .get_enum_attribute(AttributeLoc::Function, alwaysinline)
+                    .is_some();
+            }
+
+            function = f.get_next_function();
+        }
+
+        panic!("function {} not found", name);
+    };
+
+    // tiny is called twice but is small; big has a loop and is called twice
+    assert!(inlined("tiny"));
+    assert!(!inlined("big"));
+
+    // pong is only called once, but it is recursive through ping
+    assert!(!inlined("ping"));
+    assert!(!inlined("pong"));
+
+    // public functions are called from the dispatcher
+    assert!(!inlined("::a__"));
+}
+
+#[test]
+fn delete_struct_shares_zero_word() {
+    let ir = build_llvm_ir(
+        r##"
+        contract c {
+            struct S {
+                uint256 f1;
+                uint256 f2;
+                uint256 f3;
+                uint256 f4;
+                uint256 f5;
+                uint256 f6;
+                uint256 f7;
+                uint256 f8;
+            }
+
+            S s;
+
+            function clear() public {
+                delete s;
+            }
+        }"##,
+        &Options::default(),
+    )
+    .unoptimized;
+
+    // find the body of the clear function
+    let start = ir.find("@\"c::function::clear\"(").unwrap();
+    let end = start + ir[start..].find("\n}\n").unwrap();
+    let clear = &ir[start..end];
+
+    // one zero word and one save_storage call in a loop, rather than one of each per member
+    assert_eq!(clear.matches("@__bzero8(").count(), 1);
+    assert_eq!(clear.matches("@save_storage(").count(), 1);
+}
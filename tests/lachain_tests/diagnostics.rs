@@ -0,0 +1,362 @@
+use crate::{first_error, no_errors, parse_and_resolve};
+use solang::codegen::{codegen, Options};
+use solang::parser::pt;
+use solang::sema::ast::{Level, Namespace, Type};
+use solang::Target;
+
+#[test]
+fn assembly_datacopy() {
+    // Yul objects and the datacopy/dataoffset builtins would need inline assembly
+    // support, which is not available for any target yet
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function deploy() public {
+                assembly {
+                    datacopy(0, dataoffset("runtime"), datasize("runtime"))
+                }
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "evm assembly not supported on target LACHAIN"
+    );
+}
+
+#[test]
+fn assembly_instructions() {
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function f() public {
+                assembly {
+                    let x := sload(0)
+                    sstore(1, add(x, caller()))
+                }
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    let errors: Vec<_> = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Error)
+        .collect();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(
+        errors[0].message,
+        "evm assembly not supported on target LACHAIN"
+    );
+
+    let notes: Vec<&str> = errors[0].notes.iter().map(|n| n.message.as_str()).collect();
+
+    assert_eq!(
+        notes,
+        vec![
+            "instruction ‘sload’ not supported",
+            "instruction ‘sstore’ not supported",
+            "instruction ‘add’ not supported",
+            "instruction ‘caller’ not supported",
+        ]
+    );
+}
+
+#[test]
+fn expression_too_deep() {
+    fn contract(expr: String) -> &'static str {
+        let src = format!(
+            r##"
+            contract c {{
+                function f(uint64 a) public pure returns (uint64) {{
+                    return {};
+                }}
+            }}"##,
+            expr
+        );
+
+        Box::leak(src.into_boxed_str())
+    }
+
+    // a + (a + (a + ...)) with the innermost term at the given depth
+    fn nested_sum(depth: usize) -> &'static str {
+        contract(format!(
+            "{}a{}",
+            "a + (".repeat(depth - 1),
+            ")".repeat(depth - 1)
+        ))
+    }
+
+    // a + a + a + ... is a chain, not nesting
+    fn flat_sum(terms: usize) -> &'static str {
+        contract(vec!["a"; terms].join(" + "))
+    }
+
+    // the depth check must trigger long before the stack runs out, even in debug builds
+    std::thread::Builder::new()
+        .stack_size(64 * 1024 * 1024)
+        .spawn(|| {
+            let ns = parse_and_resolve(flat_sum(1000), Target::Lachain);
+
+            no_errors(ns.diagnostics);
+
+            let ns = parse_and_resolve(nested_sum(256), Target::Lachain);
+
+            no_errors(ns.diagnostics);
+
+            let ns = parse_and_resolve(nested_sum(257), Target::Lachain);
+
+            assert_eq!(
+                first_error(ns.diagnostics),
+                "expression is nested too deeply; maximum depth is 256"
+            );
+
+            // a chain is walked recursively too, so its links are limited as well
+            let ns = parse_and_resolve(flat_sum(100_000), Target::Lachain);
+
+            assert_eq!(
+                first_error(ns.diagnostics),
+                "expression has too many chained or nested operators; maximum is 1024"
+            );
+
+            // after an error, the next expression starts from depth 0 again
+            let src = format!(
+                r##"
+                contract c {{
+                    function f(uint64 a) public pure returns (uint64) {{
+                        return {};
+                    }}
+
+                    function g(uint64 a) public pure returns (uint64) {{
+                        return {};
+                    }}
+                }}"##,
+                format!("{}a{}", "a + (".repeat(256), ")".repeat(256)),
+                format!("{}a{}", "a + (".repeat(255), ")".repeat(255)),
+            );
+
+            let ns = parse_and_resolve(Box::leak(src.into_boxed_str()), Target::Lachain);
+
+            let errors: Vec<&str> = ns
+                .diagnostics
+                .iter()
+                .filter(|d| d.level == Level::Error)
+                .map(|d| d.message.as_str())
+                .collect();
+
+            assert_eq!(
+                errors,
+                vec!["expression is nested too deeply; maximum depth is 256"]
+            );
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn unreachable_function() {
+    let mut ns = parse_and_resolve(
+        r##"
+        contract c {
+            function get(uint64 a) public pure returns (uint64) {
+                return used_helper(a);
+            }
+
+            function used_helper(uint64 a) internal pure returns (uint64) {
+                return a + 1;
+            }
+
+            function unused_helper(uint64 a) internal pure returns (uint64) {
+                return a + 2;
+            }
+
+            function pointer_target(uint64 a) private pure returns (uint64) {
+                return a + 3;
+            }
+
+            function by_pointer(uint64 a) public pure returns (uint64) {
+                function(uint64) internal pure returns (uint64) f = pointer_target;
+
+                return f(a);
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    codegen(&mut ns, &Options::default());
+
+    let warnings: Vec<&str> = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning)
+        .map(|d| d.message.as_str())
+        .filter(|m| m.ends_with("is never called"))
+        .collect();
+
+    assert_eq!(warnings, vec!["function ‘unused_helper’ is never called"]);
+}
+
+#[test]
+fn encode_call_arguments() {
+    let ns = parse_and_resolve(
+        r##"
+        interface IERC20 {
+            function transfer(address to, uint256 amount) external returns (bool);
+        }
+
+        contract c {
+            function f(address to) public pure returns (bytes) {
+                return abi.encodeCall(IERC20.transfer, (to));
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "function expects 2 arguments, 1 provided"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        interface IERC20 {
+            function transfer(address to, uint256 amount) external returns (bool);
+        }
+
+        contract c {
+            function f(bool to, uint256 amt) public pure returns (bytes) {
+                return abi.encodeCall(IERC20.transfer, (to, amt));
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "argument 1 of abi.encodeCall is ‘bool’, but function ‘transfer’ expects ‘address’"
+    );
+}
+
+#[test]
+fn bytes_wider_than_32() {
+    // the lexer only produces bytes1 to bytes32, so construct the type directly
+    let mut ns = Namespace::new(Target::Lachain, 20, 32);
+    let mut diagnostics = Vec::new();
+
+    let ty = pt::Expression::Type(pt::Loc(0, 0, 0), pt::Type::Bytes(33));
+
+    assert!(ns
+        .resolve_type(0, None, false, &ty, &mut diagnostics)
+        .is_err());
+    assert_eq!(
+        first_error(diagnostics),
+        "type ‘bytes33’ not supported, maximum is ‘bytes32’"
+    );
+
+    let ty = pt::Expression::Type(pt::Loc(0, 0, 0), pt::Type::Bytes(32));
+
+    assert_eq!(
+        ns.resolve_type(0, None, false, &ty, &mut Vec::new()),
+        Ok(Type::Bytes(32))
+    );
+}
+
+#[test]
+fn new_abstract_contract() {
+    // create_contract is never reached for these, since sema rejects them
+    let ns = parse_and_resolve(
+        r##"
+        abstract contract AbstractC {
+            function f() public virtual returns (uint64);
+        }
+
+        contract c {
+            function create() public {
+                AbstractC a = new AbstractC();
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "cannot construct ‘AbstractC’ of type ‘abstract contract’"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        interface I {
+            function f() external returns (uint64);
+        }
+
+        contract c {
+            function create() public {
+                I a = new I();
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "cannot construct ‘I’ of type ‘interface’"
+    );
+
+    // a contract with functions without a body has to be declared abstract
+    let ns = parse_and_resolve(
+        r##"
+        contract C {
+            function f() public virtual returns (uint64);
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "contract should be marked ‘abstract contract’ since it has 1 functions with no body"
+    );
+}
+
+#[test]
+fn local_shadows_state_variable() {
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            uint64 total;
+
+            function add(uint64 a) public returns (uint64) {
+                uint64 total = a + 1;
+
+                return total;
+            }
+
+            function get() public view returns (uint64) {
+                return total;
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    assert!(!ns.diagnostics.iter().any(|d| d.level == Level::Error));
+
+    let warnings: Vec<_> = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning && d.message.contains("shadows"))
+        .collect();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].message,
+        "declaration of ‘total’ shadows state variable"
+    );
+    assert_eq!(
+        warnings[0].notes[0].message,
+        "previous declaration of state variable"
+    );
+}
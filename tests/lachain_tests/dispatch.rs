@@ -0,0 +1,260 @@
+use crate::build_solidity;
+use ethabi::Token;
+use ethereum_types::U256;
+
+#[test]
+fn fallback_sees_selector() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 len;
+            bytes1 first;
+            bytes1 last;
+
+            fallback() external {
+                len = uint32(msg.data.length);
+                first = msg.data[0];
+                last = msg.data[msg.data.length - 1];
+            }
+
+            function get() public view returns (uint32, bytes1, bytes1) {
+                return (len, first, last);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let mut calldata = vec![0xde, 0xad, 0xbe, 0xef];
+    calldata.extend_from_slice(&[0u8; 31]);
+    calldata.push(0x7f);
+
+    assert_eq!(calldata.len(), 36);
+    assert!(runtime.invoke(calldata));
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(36)),
+            Token::FixedBytes(vec![0xde]),
+            Token::FixedBytes(vec![0x7f]),
+        ]
+    );
+}
+
+#[test]
+fn non_payable_fallback_rejects_value() {
+    // the payable function means value transfers are not aborted before dispatch, so the
+    // fallback path has to reject the value itself
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 calls;
+
+            fallback() external {
+                calls += 1;
+            }
+
+            function pay() public payable {}
+
+            function get() public view returns (uint32) {
+                return calls;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let calldata = vec![0xde, 0xad, 0xbe, 0xef];
+
+    runtime.value = 100;
+
+    assert!(!runtime.invoke(calldata.clone()));
+
+    runtime.value = 0;
+
+    assert!(runtime.invoke(calldata.clone()));
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(1))]);
+
+    // without any payable function, value is rejected before dispatch
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            fallback() external {}
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.value = 100;
+
+    assert!(!runtime.invoke(calldata.clone()));
+
+    runtime.value = 0;
+
+    assert!(runtime.invoke(calldata));
+}
+
+#[test]
+fn fallback_and_receive() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 fallbacks;
+            uint32 receives;
+            uint128 received;
+
+            fallback() external {
+                fallbacks += 1;
+            }
+
+            receive() external payable {
+                receives += 1;
+                received += msg.value;
+            }
+
+            function get() public view returns (uint32, uint32, uint128) {
+                return (fallbacks, receives, received);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // value with empty calldata goes to receive
+    runtime.value = 500;
+
+    assert!(runtime.invoke(Vec::new()));
+
+    // an unknown selector goes to fallback
+    runtime.value = 0;
+
+    assert!(runtime.invoke(vec![0x12, 0x34, 0x56, 0x78]));
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::from(500)),
+        ]
+    );
+
+    // with neither, the call reverts
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function get() public pure returns (uint32) {
+                return 1;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    assert!(!runtime.invoke(vec![0x12, 0x34, 0x56, 0x78]));
+    assert!(!runtime.invoke(Vec::new()));
+}
+
+#[test]
+fn receive_with_calldata() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint32 fallbacks;
+            uint32 receives;
+
+            fallback() external payable {
+                fallbacks += 1;
+            }
+
+            receive() external payable {
+                receives += 1;
+            }
+
+            function get() public view returns (uint32, uint32) {
+                return (fallbacks, receives);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    // value with non-empty calldata goes to fallback, not receive
+    runtime.value = 500;
+
+    assert!(runtime.invoke(vec![0x12, 0x34, 0x56, 0x78]));
+    assert!(runtime.invoke(vec![0x01]));
+
+    runtime.value = 0;
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(2)), Token::Uint(U256::from(0))]
+    );
+
+    // value with empty calldata goes to receive
+    runtime.value = 500;
+
+    assert!(runtime.invoke(Vec::new()));
+
+    runtime.value = 0;
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(2)), Token::Uint(U256::from(1))]
+    );
+}
+
+#[test]
+fn internal_library() {
+    // libraries are not deployed on their own; internal library functions are compiled into
+    // the contract which uses them, which here exposes no functions either
+    let mut runtime = build_solidity(
+        r##"
+        library math {
+            function double(uint64 x) internal pure returns (uint64) {
+                return x * 2;
+            }
+
+            function triple(uint64 x) internal pure returns (uint64) {
+                return x * 3;
+            }
+        }
+
+        contract c {
+            using math for uint64;
+
+            uint64 value;
+
+            constructor(uint64 x) {
+                value = x.double().triple();
+            }
+        }"##,
+    );
+
+    assert_eq!(runtime.contracts.len(), 1);
+
+    runtime.constructor(&[Token::Uint(U256::from(7))]);
+
+    assert_eq!(runtime.store.len(), 1);
+    assert!(runtime
+        .store
+        .values()
+        .any(|v| v[..8] == 42u64.to_le_bytes()));
+
+    // there is no function to call
+    assert!(!runtime.invoke(vec![0x12, 0x34, 0x56, 0x78]));
+    assert!(!runtime.invoke(Vec::new()));
+}
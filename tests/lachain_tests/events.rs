@@ -0,0 +1,158 @@
+use crate::{build_solidity, first_error, first_warning, parse_and_resolve};
+use ethabi::Token;
+use solang::abi::ethereum::gen_abi;
+use solang::Target;
+
+#[test]
+fn indexed_string() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            event Log(string indexed s, uint64 n);
+
+            function log(string memory s) public {
+                emit Log(s, 102);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.function("log", &[Token::String("foobar".to_string())]);
+
+    // the log has no topics, so only the non-indexed fields are logged
+    assert_eq!(
+        runtime.events,
+        vec![ethabi::encode(&[Token::Uint(ethabi::Uint::from(102))])]
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            event Log(string indexed s, uint64 n);
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_warning(ns.diagnostics),
+        "indexed fields of event ‘Log’ are not logged on Lachain, since its log has no topics"
+    );
+}
+
+#[test]
+fn anonymous_four_indexed() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            event Transfer(address indexed from, address indexed to, uint256 indexed amount, bool indexed ok) anonymous;
+
+            function log(address from, address to) public {
+                emit Transfer(from, to, 1000, true);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.function(
+        "log",
+        &[
+            Token::Address(ethabi::Address::from([0x11u8; 20])),
+            Token::Address(ethabi::Address::from([0x22u8; 20])),
+        ],
+    );
+
+    // neither the signature nor the indexed fields are logged
+    assert_eq!(runtime.events, vec![Vec::<u8>::new()]);
+}
+
+#[test]
+fn too_many_indexed_fields() {
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            event E(uint64 indexed a, uint64 indexed b, uint64 indexed c, uint64 indexed d);
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "event definition for ‘E’ has 4 indexed fields where 3 permitted"
+    );
+
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            event E(uint64 indexed a, uint64 indexed b, uint64 indexed c, uint64 indexed d, uint64 indexed e) anonymous;
+        }"##,
+        Target::Lachain,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "anonymous event definition for ‘E’ has 5 indexed fields where 4 permitted"
+    );
+}
+
+#[test]
+fn event_abi() {
+    let ns = parse_and_resolve(
+        r##"
+        contract base {
+            event Transfer(address indexed from, address indexed to, uint256 value);
+            event Unused(uint64 id);
+        }
+
+        contract c is base {
+            event Note(uint64 id, string indexed name, bool flag, bytes32 indexed tag);
+
+            function f(address to) public {
+                emit Transfer(address(this), to, 1);
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    let abi = gen_abi(1, &ns);
+
+    // events declared in the contract are listed even if they are never emitted, events
+    // declared in a base only if they are emitted
+    let events = abi
+        .iter()
+        .filter(|abi| abi.ty == "event")
+        .map(|abi| {
+            (
+                abi.name.as_str(),
+                abi.inputs
+                    .iter()
+                    .map(|param| (param.name.as_str(), param.ty.as_str(), param.indexed))
+                    .collect::<Vec<(&str, &str, bool)>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        events,
+        vec![
+            (
+                "Transfer",
+                vec![
+                    ("from", "address", true),
+                    ("to", "address", true),
+                    ("value", "uint256", false)
+                ]
+            ),
+            (
+                "Note",
+                vec![
+                    ("id", "uint64", false),
+                    ("name", "string", true),
+                    ("flag", "bool", false),
+                    ("tag", "bytes32", true)
+                ]
+            ),
+        ]
+    );
+}
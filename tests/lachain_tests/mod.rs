@@ -0,0 +1,12 @@
+mod abi;
+mod builtins;
+mod calls;
+mod constructor;
+mod debug;
+mod diagnostics;
+mod dispatch;
+mod events;
+mod options;
+mod reproducible;
+mod storage;
+mod value;
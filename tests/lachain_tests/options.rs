@@ -0,0 +1,443 @@
+use crate::build_solidity_with_options;
+use ethabi::Token;
+use ethereum_types::U256;
+use solang::codegen::{codegen, Options};
+use solang::emit::Generate;
+use solang::file_cache::FileCache;
+use solang::sema::ast::Level;
+use solang::sema::diagnostics;
+use solang::{parse_and_resolve, Target};
+use std::collections::HashMap;
+
+#[test]
+fn contract_opt_level() {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r##"
+        contract hot {
+            uint64 count;
+
+            function inc(uint64 n) public {
+                for (uint64 i = 0; i < n; i++) {
+                    count += add(i, 1);
+                }
+            }
+
+            function add(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a + b;
+            }
+        }
+
+        contract cold {
+            uint64 count;
+
+            function inc(uint64 n) public {
+                for (uint64 i = 0; i < n; i++) {
+                    count += add(i, 1);
+                }
+            }
+
+            function add(uint64 a, uint64 b) internal pure returns (uint64) {
+                return a + b;
+            }
+        }"##
+        .to_string(),
+    );
+
+    let mut ns = parse_and_resolve("test.sol", &mut cache, Target::Lachain);
+
+    let mut contract_opt_level = HashMap::new();
+
+    contract_opt_level.insert("hot".to_string(), inkwell::OptimizationLevel::Aggressive);
+    contract_opt_level.insert("cold".to_string(), inkwell::OptimizationLevel::None);
+
+    assert_eq!(
+        Options {
+            contract_opt_level: contract_opt_level.clone(),
+            ..Default::default()
+        }
+        .opt_level_for("other"),
+        inkwell::OptimizationLevel::Default
+    );
+
+    codegen(
+        &mut ns,
+        &Options {
+            contract_opt_level,
+            ..Default::default()
+        },
+    );
+
+    assert!(!diagnostics::any_errors(&ns.diagnostics));
+
+    let hot = &ns.contracts[0];
+    let cold = &ns.contracts[1];
+
+    assert_eq!(hot.name, "hot");
+    assert_eq!(cold.name, "cold");
+
+    // the contracts are identical apart from their name, so any difference in the
+    // output comes from the optimization level
+    assert_ne!(hot.code, cold.code);
+    assert!(hot.code.len() < cold.code.len());
+}
+
+#[test]
+fn contract_size_limit() {
+    let src = r##"
+        contract c {
+            string name;
+
+            function set(string n) public {
+                name = string(abi.encodePacked(n, " and ", n));
+            }
+
+            function get() public view returns (string) {
+                return name;
+            }
+        }"##;
+
+    let build = |opt: &Options| {
+        let mut cache = FileCache::new();
+
+        cache.set_file_contents("test.sol", src.to_string());
+
+        let mut ns = parse_and_resolve("test.sol", &mut cache, Target::Lachain);
+
+        codegen(&mut ns, opt);
+
+        ns
+    };
+
+    // the default limit is the EIP-170 24KB
+    let ns = build(&Options::default());
+
+    assert!(!ns.contracts[0].code.is_empty());
+    assert!(ns.contracts[0].code.len() < 24576);
+    assert!(!ns
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("exceeds limit")));
+
+    let ns = build(&Options {
+        contract_size_limit: Some(100),
+        ..Default::default()
+    });
+
+    let size = ns.contracts[0].code.len();
+
+    let warnings: Vec<_> = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning && d.message.contains("exceeds limit"))
+        .collect();
+
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+        warnings[0].message,
+        format!(
+            "contract ‘c’ code size of {} bytes exceeds limit of 100 bytes",
+            size
+        )
+    );
+    assert!(!diagnostics::any_errors(&ns.diagnostics));
+
+    let ns = build(&Options {
+        contract_size_limit: Some(100),
+        contract_size_limit_error: true,
+        ..Default::default()
+    });
+
+    assert!(diagnostics::any_errors(&ns.diagnostics));
+
+    // ewasm code is the deployer, so the EIP-170 default does not apply to it
+    let opt = Options::default();
+
+    assert_eq!(opt.contract_size_limit_for(Target::Lachain), Some(24576));
+    assert_eq!(opt.contract_size_limit_for(Target::Ewasm), None);
+}
+
+#[test]
+fn unchecked_with_overflow_check() {
+    let mut runtime = build_solidity_with_options(
+        r##"
+        contract c {
+            function checked_add(uint64 x, uint64 y) public pure returns (uint64) {
+                return x + y;
+            }
+
+            function unchecked_add(uint64 x, uint64 y) public pure returns (uint64) {
+                unchecked {
+                    return x + y;
+                }
+            }
+
+            function unchecked_ops(uint64 x, uint64 y) public pure returns (uint64, uint64, uint64) {
+                uint64 a;
+                uint64 b;
+                uint64 c = x;
+
+                unchecked {
+                    a = y - x;
+                    b = x * y;
+                    c++;
+                }
+
+                return (a, b, c);
+            }
+        }"##,
+        &Options {
+            math_overflow_check: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let max = Token::Uint(U256::from(u64::MAX));
+    let two = Token::Uint(U256::from(2));
+
+    let calldata = runtime.abi.functions["checked_add"][0]
+        .encode_input(&[max.clone(), two.clone()])
+        .unwrap();
+
+    assert!(!runtime.invoke(calldata));
+
+    let returns = runtime.function("unchecked_add", &[max.clone(), two.clone()]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(1))]);
+
+    let returns = runtime.function("unchecked_ops", &[max, two]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(U256::from(3)),
+            Token::Uint(U256::from(u64::MAX - 1)),
+            Token::Uint(U256::from(0))
+        ]
+    );
+}
+
+#[test]
+fn overflow_panic() {
+    let mut runtime = build_solidity_with_options(
+        r##"
+        contract c {
+            function add(uint256 x, uint256 y) public pure returns (uint256) {
+                return x + y;
+            }
+        }"##,
+        &Options {
+            math_overflow_check: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let calldata = runtime.abi.functions["add"][0]
+        .encode_input(&[
+            Token::Uint(U256::max_value()),
+            Token::Uint(U256::from(1) << 255),
+        ])
+        .unwrap();
+
+    assert!(!runtime.invoke(calldata));
+
+    // Panic(uint256) selector followed by the panic code
+    assert_eq!(&runtime.vm.output[..4], &[0x4e, 0x48, 0x7b, 0x71]);
+
+    let code = ethabi::decode(&[ethabi::ParamType::Uint(256)], &runtime.vm.output[4..]).unwrap();
+
+    assert_eq!(code, vec![Token::Uint(U256::from(0x11))]);
+}
+
+#[test]
+fn hash_host_byte_order() {
+    let src = r##"
+        contract c {
+            function hash(bytes b) public pure returns (bytes32) {
+                return keccak256(b);
+            }
+        }"##;
+
+    let mut hash = [0u8; 32];
+    let mut hasher = tiny_keccak::Keccak::v256();
+    tiny_keccak::Hasher::update(&mut hasher, b"Lachain");
+    tiny_keccak::Hasher::finalize(hasher, &mut hash);
+
+    let args = [Token::Bytes(b"Lachain".to_vec())];
+
+    let mut runtime = build_solidity_with_options(src, &Options::default());
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("hash", &args);
+
+    assert_eq!(returns, vec![Token::FixedBytes(hash.to_vec())]);
+
+    // without the reversal, the bytes end up in the opposite order
+    let mut runtime = build_solidity_with_options(
+        src,
+        &Options {
+            hash_host_byte_order: true,
+            ..Default::default()
+        },
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("hash", &args);
+
+    hash.reverse();
+
+    assert_eq!(returns, vec![Token::FixedBytes(hash.to_vec())]);
+}
+
+#[test]
+fn shared_child_code() {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents(
+        "test.sol",
+        r##"
+        contract child {
+            uint64 count;
+
+            function inc(uint64 n) public {
+                for (uint64 i = 0; i < n; i++) {
+                    count += 1;
+                }
+            }
+        }
+
+        contract parent_a {
+            function create() public returns (address) {
+                return address(new child());
+            }
+        }
+
+        contract parent_b {
+            function create() public returns (address) {
+                return address(new child());
+            }
+        }"##
+        .to_string(),
+    );
+
+    let mut ns = parse_and_resolve("test.sol", &mut cache, Target::Lachain);
+
+    // build the child differently from its creators, so a rebuild would be noticed
+    let mut contract_opt_level = HashMap::new();
+
+    contract_opt_level.insert("child".to_string(), inkwell::OptimizationLevel::None);
+
+    codegen(
+        &mut ns,
+        &Options {
+            contract_opt_level,
+            ..Default::default()
+        },
+    );
+
+    assert!(!diagnostics::any_errors(&ns.diagnostics));
+
+    let child = &ns.contracts[0].code;
+
+    assert!(!child.is_empty());
+
+    // each creator embeds the code which was built for the child itself
+    for parent in &ns.contracts[1..] {
+        assert!(!parent.code.is_empty());
+        assert!(
+            parent
+                .code
+                .windows(child.len())
+                .any(|w| w == child.as_slice()),
+            "contract {} does not embed the child code",
+            parent.name
+        );
+    }
+}
+
+#[test]
+fn creation_code_with_options() {
+    let src = r##"
+        contract child {
+            uint64 count;
+
+            function inc(uint64 n) public returns (bytes32) {
+                count += n;
+
+                return keccak256(abi.encode(count));
+            }
+        }
+
+        contract parent {
+            function code() public pure returns (bytes) {
+                return type(child).creationCode;
+            }
+
+            function create() public returns (address) {
+                return address(new child());
+            }
+        }"##;
+
+    // options which change the code of the child, so that building it with the default options
+    // would be noticed
+    let mut contract_opt_level = HashMap::new();
+
+    contract_opt_level.insert("child".to_string(), inkwell::OptimizationLevel::None);
+
+    let opt = Options {
+        strict_abi_decode: true,
+        max_return_data: Some(64),
+        hash_host_byte_order: true,
+        contract_opt_level,
+        ..Default::default()
+    };
+
+    let mut runtime = build_solidity_with_options(src, &opt);
+
+    runtime.constructor(&[]);
+
+    let child = runtime.contracts[0].clone();
+
+    let returns = runtime.function("code", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(child.clone())]);
+
+    let returns = runtime.function("create", &[]);
+
+    let address = match &returns[0] {
+        Token::Address(address) => address.0,
+        _ => unreachable!(),
+    };
+
+    assert_eq!(runtime.accounts[&address].0, child);
+
+    // without the code from build_all(), e.g. in the language server, the child is built with
+    // the options of its creator
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let mut ns = parse_and_resolve("test.sol", &mut cache, Target::Lachain);
+
+    codegen(&mut ns, &opt);
+
+    assert!(!diagnostics::any_errors(&ns.diagnostics));
+
+    let child = std::mem::take(&mut ns.contracts[0].code);
+
+    let context = inkwell::context::Context::create();
+
+    let parent = ns.contracts[1].emit(&ns, &context, "test.sol", &opt);
+
+    let code = parent.code(Generate::Linked).expect("llvm build");
+
+    assert!(code.windows(child.len()).any(|w| w == child.as_slice()));
+}
@@ -0,0 +1,156 @@
+use crate::build_llvm_ir;
+use solang::codegen::Options;
+use solang::file_cache::FileCache;
+use solang::{compile, Target};
+
+fn compile_lachain(src: &str, opt: inkwell::OptimizationLevel) -> Vec<Vec<u8>> {
+    let mut cache = FileCache::new();
+
+    cache.set_file_contents("test.sol", src.to_string());
+
+    let (res, _) = compile("test.sol", &mut cache, opt, Target::Lachain, false);
+
+    assert!(!res.is_empty());
+
+    res.into_iter().map(|v| v.0).collect()
+}
+
+#[test]
+fn identical_output() {
+    let src = r##"
+        contract c {
+            uint64 count;
+            string name;
+            mapping(address => uint256) balances;
+
+            event Counted(uint64 count);
+
+            function inc() public {
+                count += 1;
+                emit Counted(count);
+            }
+
+            function get() public view returns (uint64) {
+                return count;
+            }
+
+            function set_name(string n) public {
+                name = n;
+            }
+
+            function get_name() public view returns (string) {
+                return name;
+            }
+
+            function deposit(address a, uint256 v) public {
+                balances[a] += helper(v);
+            }
+
+            function helper(uint256 v) internal pure returns (uint256) {
+                require(v < 1000, "too much");
+                return v * 2;
+            }
+        }"##;
+
+    let first = compile_lachain(src, inkwell::OptimizationLevel::Default);
+    let second = compile_lachain(src, inkwell::OptimizationLevel::Default);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn identical_output_unoptimized() {
+    // without optimization, every local variable keeps its own alloca, and every string
+    // literal its own global, so the order in which these are emitted shows in the output
+    let src = r##"
+        contract child {
+            string greeting = "hello";
+
+            function greet(uint64 n) public view returns (string) {
+                uint64 a = n + 1;
+                uint64 b = a * 2;
+                uint64 c = b - n;
+                bool d = c > a;
+                string memory s = d ? "bigger" : "smaller";
+
+                return string(abi.encodePacked(greeting, " ", s));
+            }
+        }
+
+        contract parent {
+            function make(uint64 n) public returns (string) {
+                child c = new child();
+                int32 x = 1;
+                int64 y = 2;
+                uint128 z = 3;
+                bytes4 w = 0x01020304;
+
+                require(x + int32(y) + int32(uint32(z)) == 6, "bad sum");
+                require(w != 0, "bad bytes");
+
+                return c.greet(n);
+            }
+        }"##;
+
+    let first = compile_lachain(src, inkwell::OptimizationLevel::None);
+
+    for _ in 0..4 {
+        assert_eq!(
+            first,
+            compile_lachain(src, inkwell::OptimizationLevel::None)
+        );
+    }
+}
+
+/// Compile the source, and return the global definitions in the order they appear in the
+/// unoptimized ir of the last contract
+fn lachain_globals(src: &str) -> Vec<String> {
+    build_llvm_ir(src, &Options::default())
+        .unoptimized
+        .lines()
+        .filter(|line| line.starts_with('@'))
+        .map(|line| line.to_string())
+        .collect()
+}
+
+#[test]
+fn global_string_order() {
+    // event topics, revert reasons, string literals and the code of the child contract
+    // all become globals
+    let src = r##"
+        contract child {
+            function hi() public pure returns (string) {
+                return "hi";
+            }
+        }
+
+        contract c {
+            event First(uint64 a);
+            event Second(string b);
+
+            function a(uint64 x) public {
+                require(x > 1, "x too small");
+                emit First(x);
+            }
+
+            function b(string s) public {
+                require(bytes(s).length < 10, "s too long");
+                emit Second(s);
+            }
+
+            function make() public returns (string) {
+                child ch = new child();
+
+                return string(abi.encodePacked(ch.hi(), " there"));
+            }
+        }"##;
+
+    let first = lachain_globals(src);
+
+    assert!(first.iter().any(|g| g.contains("x too small")));
+    assert!(first.iter().any(|g| g.contains("s too long")));
+
+    for _ in 0..4 {
+        assert_eq!(first, lachain_globals(src));
+    }
+}
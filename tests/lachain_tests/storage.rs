@@ -0,0 +1,688 @@
+use crate::{build_llvm_ir, build_solidity, parse_and_resolve};
+use ethabi::Token;
+use solang::codegen::cfg::Instr;
+use solang::codegen::{codegen, Options};
+use solang::sema::ast::Expression;
+use solang::sema::diagnostics;
+use solang::Target;
+use tiny_keccak::{Hasher, Keccak};
+
+#[test]
+fn bool_and_uint8_struct_fields() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            struct S {
+                bool flag;
+                uint8 small;
+            }
+
+            S s;
+
+            function set_flag(bool f) public {
+                s.flag = f;
+            }
+
+            function set_small(uint8 v) public {
+                s.small = v;
+            }
+
+            function get() public view returns (bool, uint8) {
+                return (s.flag, s.small);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.function("set_small", &[Token::Uint(ethereum_types::U256::from(7))]);
+
+    runtime.function("set_flag", &[Token::Bool(true)]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bool(true),
+            Token::Uint(ethereum_types::U256::from(7))
+        ]
+    );
+
+    runtime.function("set_flag", &[Token::Bool(false)]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bool(false),
+            Token::Uint(ethereum_types::U256::from(7))
+        ]
+    );
+
+    runtime.function("set_small", &[Token::Uint(ethereum_types::U256::from(0))]);
+    runtime.function("set_flag", &[Token::Bool(true)]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Bool(true),
+            Token::Uint(ethereum_types::U256::from(0))
+        ]
+    );
+}
+
+#[test]
+fn uninitialized_storage_string() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            string s;
+            bytes b;
+
+            function string_length() public view returns (uint32) {
+                return uint32(bytes(s).length);
+            }
+
+            function bytes_length() public view returns (uint32) {
+                return uint32(b.length);
+            }
+
+            function get_string() public view returns (string) {
+                return s;
+            }
+
+            function set_string(string v) public {
+                s = v;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("string_length", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(0))]);
+
+    let returns = runtime.function("bytes_length", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethereum_types::U256::from(0))]);
+
+    let returns = runtime.function("get_string", &[]);
+
+    assert_eq!(returns, vec![Token::String(String::from(""))]);
+
+    runtime.function("set_string", &[Token::String(String::from("lachain"))]);
+
+    let returns = runtime.function("get_string", &[]);
+
+    assert_eq!(returns, vec![Token::String(String::from("lachain"))]);
+}
+
+#[test]
+fn dependent_immutables() {
+    let mut ns = parse_and_resolve(
+        r##"
+        contract c {
+            uint64 immutable a = 41;
+            uint64 immutable b = a + 1;
+
+            function get() public view returns (uint64, uint64) {
+                return (a, b);
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    codegen(&mut ns, &Options::default());
+
+    assert!(!diagnostics::any_errors(&ns.diagnostics));
+
+    let contract = &ns.contracts[0];
+    let initializer = &contract.cfg[contract.initializer.unwrap()];
+
+    // the initializer must store a before it evaluates b, since b reads a from storage
+    let slots: Vec<String> = initializer
+        .blocks
+        .iter()
+        .flat_map(|block| block.instr.iter())
+        .filter_map(|instr| match instr {
+            Instr::SetStorage {
+                storage: Expression::NumberLiteral(_, _, slot),
+                ..
+            } => Some(slot.to_string()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(slots, vec!["0", "1"]);
+}
+
+#[test]
+fn pure_contract_has_no_storage_imports() {
+    fn imports(code: &[u8]) -> Vec<String> {
+        let module: parity_wasm::elements::Module = parity_wasm::deserialize_buffer(code).unwrap();
+
+        module
+            .import_section()
+            .map(|section| {
+                section
+                    .entries()
+                    .iter()
+                    .map(|entry| entry.field().to_owned())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    let storage_externals = [
+        "load_storage",
+        "save_storage",
+        "load_storage_string",
+        "save_storage_string",
+        "get_storage_string_size",
+    ];
+
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function add(uint64 a, uint64 b) public pure returns (uint64) {
+                return a + b;
+            }
+
+            function hash(bytes memory data) public pure returns (bytes32) {
+                return keccak256(data);
+            }
+        }"##,
+    );
+
+    let imports_pure = imports(&runtime.contracts[0]);
+
+    assert!(imports_pure
+        .iter()
+        .all(|import| !storage_externals.contains(&import.as_str())));
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function(
+        "add",
+        &[
+            Token::Uint(ethabi::Uint::from(40)),
+            Token::Uint(ethabi::Uint::from(2)),
+        ],
+    );
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(42))]);
+
+    // a contract with state does import them
+    let runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 total;
+
+            function add(uint64 a) public returns (uint64) {
+                total += a;
+                return total;
+            }
+        }"##,
+    );
+
+    let imports_state = imports(&runtime.contracts[0]);
+
+    assert!(imports_state.iter().any(|import| import == "load_storage"));
+    assert!(imports_state.iter().any(|import| import == "save_storage"));
+}
+
+#[test]
+fn constructor_storage_writes() {
+    // Lachain has no batched storage write, so each state variable set in the
+    // constructor costs exactly one save_storage call; make sure codegen does
+    // not add redundant writes on top of that.
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64 v0;
+            uint64 v1;
+            uint64 v2;
+            uint64 v3;
+            uint64 v4;
+            uint64 v5;
+            uint64 v6;
+            uint64 v7;
+            uint64 v8;
+            uint64 v9;
+
+            constructor() {
+                v0 = 100;
+                v1 = 101;
+                v2 = 102;
+                v3 = 103;
+                v4 = 104;
+                v5 = 105;
+                v6 = 106;
+                v7 = 107;
+                v8 = 108;
+                v9 = 109;
+            }
+
+            function sum() public view returns (uint64) {
+                return v0 + v1 + v2 + v3 + v4 + v5 + v6 + v7 + v8 + v9;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    assert_eq!(runtime.storage_writes, 10);
+
+    let returns = runtime.function("sum", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(1045))]);
+    assert_eq!(runtime.storage_writes, 10);
+}
+
+#[test]
+fn nested_mapping() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            mapping(address => mapping(address => uint256)) allowance;
+
+            function approve(address owner, address spender, uint256 amount) public {
+                allowance[owner][spender] = amount;
+            }
+
+            function get(address owner, address spender) public view returns (uint256) {
+                return allowance[owner][spender];
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let a = Token::Address(ethabi::Address::from(crate::address_new()));
+    let b = Token::Address(ethabi::Address::from(crate::address_new()));
+    let c = Token::Address(ethabi::Address::from(crate::address_new()));
+
+    runtime.function("approve", &[a.clone(), b.clone(), Token::Uint(5.into())]);
+    runtime.function("approve", &[a.clone(), c.clone(), Token::Uint(7.into())]);
+
+    assert_eq!(runtime.store.len(), 2);
+
+    let returns = runtime.function("get", &[a.clone(), b.clone()]);
+
+    assert_eq!(returns, vec![Token::Uint(5.into())]);
+
+    let returns = runtime.function("get", &[a.clone(), c.clone()]);
+
+    assert_eq!(returns, vec![Token::Uint(7.into())]);
+
+    // the keys are not interchangeable
+    let returns = runtime.function("get", &[b.clone(), a.clone()]);
+
+    assert_eq!(returns, vec![Token::Uint(0.into())]);
+
+    let returns = runtime.function("get", &[c, b]);
+
+    assert_eq!(returns, vec![Token::Uint(0.into())]);
+}
+
+#[test]
+fn storage_array_length() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64[] values;
+
+            function push(uint64 v) public {
+                values.push(v);
+            }
+
+            function pop() public {
+                values.pop();
+            }
+
+            function len() public view returns (uint256) {
+                return values.length;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(0))]);
+
+    runtime.function("push", &[Token::Uint(ethabi::Uint::from(10))]);
+    runtime.function("push", &[Token::Uint(ethabi::Uint::from(20))]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(2))]);
+
+    runtime.function("pop", &[]);
+
+    let returns = runtime.function("len", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(1))]);
+}
+
+#[test]
+fn storage_array_out_of_bounds() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint64[] values;
+            uint64[3] fixed;
+
+            function push(uint64 v) public {
+                values.push(v);
+            }
+
+            function get(uint256 i) public view returns (uint64) {
+                return values[i];
+            }
+
+            function set_fixed(uint256 i, uint64 v) public {
+                fixed[i] = v;
+            }
+
+            function get_memory(uint256 i) public pure returns (uint64) {
+                uint64[] memory m = new uint64[](2);
+
+                return m[i];
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.function("push", &[Token::Uint(ethabi::Uint::from(10))]);
+
+    let returns = runtime.function("get", &[Token::Uint(ethabi::Uint::from(0))]);
+
+    assert_eq!(returns, vec![Token::Uint(ethabi::Uint::from(10))]);
+
+    // Panic(uint256) selector followed by code 0x32, for storage and memory arrays alike
+    let mut panic = vec![0x4e, 0x48, 0x7b, 0x71];
+    panic.extend(ethabi::encode(&[Token::Uint(ethabi::Uint::from(0x32))]));
+
+    for (name, args) in &[
+        ("get", vec![Token::Uint(ethabi::Uint::from(1))]),
+        (
+            "set_fixed",
+            vec![
+                Token::Uint(ethabi::Uint::from(3)),
+                Token::Uint(ethabi::Uint::from(1)),
+            ],
+        ),
+        ("get_memory", vec![Token::Uint(ethabi::Uint::from(2))]),
+    ] {
+        let calldata = runtime.abi.functions[*name][0].encode_input(args).unwrap();
+
+        assert!(!runtime.invoke(calldata));
+        assert_eq!(runtime.vm.output, panic);
+    }
+}
+
+#[test]
+fn storage_bytes_append() {
+    let src = r##"
+        contract c {
+            bytes log;
+
+            function append(bytes extra) public {
+                log = bytes.concat(log, extra);
+            }
+
+            function add(bytes extra) public {
+                log = log + extra;
+            }
+
+            function get() public view returns (bytes) {
+                return log;
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    runtime.function("append", &[Token::Bytes(b"hello, ".to_vec())]);
+    runtime.function("append", &[Token::Bytes(b"world".to_vec())]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(b"hello, world".to_vec())]);
+
+    runtime.function("add", &[Token::Bytes(b"!".to_vec())]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(b"hello, world!".to_vec())]);
+
+    // the append is a single instruction, rather than a load, concatenation and store
+    let mut ns = parse_and_resolve(src, Target::Lachain);
+
+    codegen(&mut ns, &Options::default());
+
+    let contract = &ns.contracts[0];
+
+    for name in &["append__bytes", "add__bytes"] {
+        let cfg = contract
+            .cfg
+            .iter()
+            .find(|cfg| cfg.name.ends_with(name))
+            .unwrap();
+
+        let instrs: Vec<&Instr> = cfg.blocks.iter().flat_map(|b| b.instr.iter()).collect();
+
+        assert!(instrs
+            .iter()
+            .any(|instr| matches!(instr, Instr::AppendStorageBytes { .. })));
+        assert!(!instrs
+            .iter()
+            .any(|instr| matches!(instr, Instr::LoadStorage { .. })));
+    }
+}
+
+#[test]
+fn storage_bytes_append_not_independent() {
+    let src = r##"
+        contract c {
+            bytes log;
+
+            function twice() public {
+                log = bytes.concat(log, log);
+            }
+
+            function reset() internal returns (bytes) {
+                log = hex"ff";
+                return hex"01";
+            }
+
+            function after_reset() public {
+                log = bytes.concat(log, reset());
+            }
+
+            function get() public view returns (bytes) {
+                return log;
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    runtime.function("after_reset", &[]);
+
+    let returns = runtime.function("get", &[]);
+
+    // the current value is read before reset() is called
+    assert_eq!(returns, vec![Token::Bytes(vec![1])]);
+
+    runtime.function("twice", &[]);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Bytes(vec![1, 1])]);
+
+    // neither is rewritten to an append, since the value refers to log or has side effects
+    let mut ns = parse_and_resolve(src, Target::Lachain);
+
+    codegen(&mut ns, &Options::default());
+
+    let contract = &ns.contracts[0];
+
+    for name in &["twice", "after_reset"] {
+        let cfg = contract
+            .cfg
+            .iter()
+            .find(|cfg| cfg.name.ends_with(name))
+            .unwrap();
+
+        assert!(!cfg
+            .blocks
+            .iter()
+            .flat_map(|b| b.instr.iter())
+            .any(|instr| matches!(instr, Instr::AppendStorageBytes { .. })));
+    }
+}
+
+#[test]
+fn bytes_concat_copies() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function test(bytes b) public returns (bytes, bytes) {
+                bytes c = bytes.concat(b);
+
+                c[0] = 0xff;
+
+                return (b, c);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("test", &[Token::Bytes(vec![1, 2, 3])]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Bytes(vec![1, 2, 3]), Token::Bytes(vec![0xff, 2, 3])]
+    );
+}
+
+#[test]
+fn delete_large_struct() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            struct S {
+                uint256 f1;
+                uint256 f2;
+                uint256 f3;
+                uint256 f4;
+                uint256 f5;
+                uint256 f6;
+                uint256 f7;
+                uint256 f8;
+            }
+
+            S s;
+            uint64 tail;
+
+            function set() public {
+                s = S(1, 2, 3, 4, 5, 6, 7, 8);
+                tail = 9;
+            }
+
+            function clear() public {
+                delete s;
+            }
+
+            function get() public view returns (uint256, uint256, uint64) {
+                return (s.f1, s.f8, tail);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.function("set", &[]);
+
+    assert_eq!(runtime.store.len(), 9);
+
+    runtime.function("clear", &[]);
+
+    // all members are zeroed, and the variable after the struct is left alone
+    assert_eq!(runtime.store.len(), 1);
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![
+            Token::Uint(ethereum_types::U256::zero()),
+            Token::Uint(ethereum_types::U256::zero()),
+            Token::Uint(ethereum_types::U256::from(9)),
+        ]
+    );
+}
+
+#[test]
+fn mapping_slot_derivation() {
+    let src = r##"
+        contract c {
+            uint64 first;
+            mapping(uint64 => uint64) m;
+
+            function set(uint64 k, uint64 v) public {
+                m[k] = v;
+            }
+        }"##;
+
+    let mut runtime = build_solidity(src);
+
+    runtime.constructor(&[]);
+
+    runtime.function(
+        "set",
+        &[
+            Token::Uint(ethereum_types::U256::from(0x0102_0304_0506_0708u64)),
+            Token::Uint(ethereum_types::U256::from(9)),
+        ],
+    );
+
+    // the slot of the entry is the hash of the mapping slot followed by the key, both in
+    // host byte order
+    let mut buf = [0u8; 32 + 8];
+
+    buf[0] = 1;
+    buf[32..].copy_from_slice(&0x0102_0304_0506_0708u64.to_le_bytes());
+
+    let mut hasher = Keccak::v256();
+    let mut slot = [0u8; 32];
+
+    hasher.update(&buf);
+    hasher.finalize(&mut slot);
+
+    assert_eq!(runtime.store.len(), 1);
+    assert_eq!(runtime.store[&(runtime.vm.cur, slot)][0], 9);
+
+    // the slot and key are written into a buffer on the stack, which is hashed with a
+    // single host call
+    let ir = build_llvm_ir(src, &Options::default()).unoptimized;
+
+    let start = ir.find("@\"c::function::set__").unwrap();
+    let end = start + ir[start..].find("\n}\n").unwrap();
+    let set = &ir[start..end];
+
+    assert_eq!(set.matches("@crypto_keccak256(").count(), 1);
+    assert!(!set.contains("@__malloc("));
+}
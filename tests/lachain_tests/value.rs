@@ -0,0 +1,346 @@
+use crate::{address_new, build_llvm_ir, build_solidity, parse_and_resolve};
+use ethabi::Token;
+use ethereum_types::U256;
+use solang::codegen::{codegen, Options};
+use solang::emit;
+use solang::parser::pt;
+use solang::sema::ast::{Level, Mutability};
+use solang::Target;
+
+#[test]
+fn msg_value() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint256 received;
+
+            receive() external payable {
+                received = msg.value;
+            }
+
+            function pay() public payable returns (uint256) {
+                return msg.value;
+            }
+
+            function get() public view returns (uint256) {
+                return received;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.value = 1_000_000_000_000_000_000;
+
+    let returns = runtime.function("pay", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(1_000_000_000_000_000_000u128))]
+    );
+
+    // receive is only reached if value_transferred() is non-zero
+    runtime.value = 0x1234_5678_9abc_def0_1234;
+
+    assert!(runtime.invoke(Vec::new()));
+
+    runtime.value = 0;
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(0x1234_5678_9abc_def0_1234u128))]
+    );
+}
+
+#[test]
+fn constructor_msg_value() {
+    let ir = build_llvm_ir(
+        r##"
+        contract c {
+            uint256 public deposit;
+
+            constructor() payable {
+                deposit = msg.value;
+            }
+        }"##,
+        &Options::default(),
+    )
+    .unoptimized;
+
+    // the constructor reads the value from the host, rather than assuming it is zero
+    let start = ir.find("@\"c::constructor::").unwrap();
+    let end = start + ir[start..].find("\n}\n").unwrap();
+    let constructor = &ir[start..end];
+
+    assert!(constructor.contains("@get_msgvalue("));
+    assert!(constructor.contains("@save_storage("));
+}
+
+#[test]
+fn send_and_transfer() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function send(address payable to, uint256 amount) public returns (bool) {
+                return to.send(amount);
+            }
+
+            function transfer(address payable to, uint256 amount) public {
+                to.transfer(amount);
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.accounts.get_mut(&runtime.vm.cur).unwrap().1 = 150;
+
+    let to = address_new();
+
+    let returns = runtime.function(
+        "send",
+        &[
+            Token::Address(ethabi::Address::from(to)),
+            Token::Uint(U256::from(100)),
+        ],
+    );
+
+    assert_eq!(returns, vec![Token::Bool(true)]);
+    assert_eq!(runtime.accounts[&to].1, 100);
+
+    // insufficient balance: send reports failure rather than reverting
+    let returns = runtime.function(
+        "send",
+        &[
+            Token::Address(ethabi::Address::from(to)),
+            Token::Uint(U256::from(100)),
+        ],
+    );
+
+    assert_eq!(returns, vec![Token::Bool(false)]);
+    assert_eq!(runtime.accounts[&to].1, 100);
+
+    // transfer reverts instead
+    let calldata = runtime.abi.functions["transfer"][0]
+        .encode_input(&[
+            Token::Address(ethabi::Address::from(to)),
+            Token::Uint(U256::from(100)),
+        ])
+        .unwrap();
+
+    assert!(!runtime.invoke(calldata));
+
+    runtime.function(
+        "transfer",
+        &[
+            Token::Address(ethabi::Address::from(to)),
+            Token::Uint(U256::from(50)),
+        ],
+    );
+
+    assert_eq!(runtime.accounts[&to].1, 150);
+    assert_eq!(runtime.accounts[&runtime.vm.cur].1, 0);
+}
+
+#[test]
+fn transfer_stipend_warning() {
+    let ns = parse_and_resolve(
+        r##"
+        contract c {
+            function pay(address payable to) public {
+                to.transfer(1);
+                to.send(1);
+            }
+        }"##,
+        Target::Lachain,
+    );
+
+    let warnings: Vec<&str> = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning && d.message.contains("stipend"))
+        .map(|d| d.message.as_str())
+        .collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "‘transfer’ on Lachain forwards all remaining gas, not the 2300 gas stipend",
+            "‘send’ on Lachain forwards all remaining gas, not the 2300 gas stipend",
+        ]
+    );
+}
+
+#[test]
+fn payable_constructor() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint256 received;
+
+            constructor() payable {
+                received = msg.value;
+            }
+
+            function get() public view returns (uint256) {
+                return received;
+            }
+        }"##,
+    );
+
+    runtime.value = 0x1234_5678_9abc_def0;
+
+    runtime.constructor(&[]);
+
+    runtime.value = 0;
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(0x1234_5678_9abc_def0u64))]
+    );
+
+    // a non-payable constructor rejects value, but deploys fine without
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            uint256 received;
+
+            constructor() {
+                received = 1;
+            }
+        }"##,
+    );
+
+    runtime.vm.cur = address_new();
+
+    runtime
+        .accounts
+        .insert(runtime.vm.cur, (runtime.contracts[0].clone(), 0));
+
+    runtime.vm.value = 1;
+
+    assert!(!runtime.execute("deploy", Vec::new()));
+
+    runtime.value = 0;
+
+    runtime.constructor(&[]);
+}
+
+#[test]
+fn inherited_payable_function() {
+    // the only payable function is in the base contract; value transfers must
+    // not be aborted up front for the derived contract
+    let mut runtime = build_solidity(
+        r##"
+        contract base {
+            function pay() public payable returns (uint256) {
+                return msg.value;
+            }
+        }
+
+        contract c is base {
+            function get() public pure returns (uint256) {
+                return 42;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    runtime.value = 1000;
+
+    let returns = runtime.function("pay", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(1000))]);
+
+    // non-payable functions still reject value
+    let calldata = runtime.abi.functions["get"][0].encode_input(&[]).unwrap();
+
+    assert!(!runtime.invoke(calldata));
+
+    runtime.value = 0;
+
+    let returns = runtime.function("get", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::from(42))]);
+}
+
+#[test]
+fn this_balance() {
+    let mut runtime = build_solidity(
+        r##"
+        contract c {
+            function balance() public view returns (uint256) {
+                return address(this).balance;
+            }
+        }"##,
+    );
+
+    runtime.constructor(&[]);
+
+    let returns = runtime.function("balance", &[]);
+
+    assert_eq!(returns, vec![Token::Uint(U256::zero())]);
+
+    runtime.accounts.get_mut(&runtime.vm.cur).unwrap().1 = 0x1234_5678_9abc_def0;
+
+    let returns = runtime.function("balance", &[]);
+
+    assert_eq!(
+        returns,
+        vec![Token::Uint(U256::from(0x1234_5678_9abc_def0u64))]
+    );
+}
+
+#[test]
+fn payable_under_value_abort() {
+    let src = r##"
+        contract base {
+            function pay() public payable returns (uint256) {
+                return msg.value;
+            }
+        }
+
+        contract c is base {
+            function get() public pure returns (uint256) {
+                return 42;
+            }
+        }"##;
+
+    let mut ns = parse_and_resolve(src, Target::Lachain);
+
+    codegen(&mut ns, &Options::default());
+
+    assert!(!ns
+        .diagnostics
+        .iter()
+        .any(|d| d.message.contains("rejects all value transfers")));
+
+    // if the binary aborted all value transfers although pay() is dispatched as payable,
+    // pay() could never receive value
+    let pay = ns.functions.iter().position(|f| f.name == "pay").unwrap();
+
+    ns.functions[pay].mutability = Mutability::Nonpayable(pt::Loc(0, 0, 0));
+
+    emit::build_all(&mut ns, &Options::default());
+
+    let warnings: Vec<&str> = ns
+        .diagnostics
+        .iter()
+        .filter(|d| d.level == Level::Warning)
+        .map(|d| d.message.as_str())
+        .filter(|m| m.contains("rejects all value transfers"))
+        .collect();
+
+    assert_eq!(
+        warnings,
+        vec![
+            "function ‘pay’ is payable, but contract ‘base’ rejects all value transfers",
+            "function ‘pay’ is payable, but contract ‘c’ rejects all value transfers"
+        ]
+    );
+}
@@ -859,6 +859,26 @@ fn payable_functions() {
     runtime.vm.value = 1;
     runtime.function("test", Vec::new());
 
+    // the only payable function is inherited
+    let mut runtime = build_solidity(
+        r##"
+        contract b {
+            function test() payable public {
+            }
+        }
+
+        contract c is b {
+            function test2() public {
+            }
+        }"##,
+    );
+
+    runtime.constructor(0, Vec::new());
+    runtime.vm.value = 1;
+    runtime.function_expect_failure("test2", Vec::new());
+    runtime.vm.value = 1;
+    runtime.function("test", Vec::new());
+
     // test fallback and receive
     #[derive(Debug, PartialEq, Encode, Decode)]
     struct Ret(u32);
@@ -243,6 +243,48 @@ fn string_concat() {
     runtime.function("test", Vec::new());
 }
 
+#[test]
+fn bytes_concat() {
+    let mut runtime = build_solidity(
+        r##"
+        contract foo {
+            bytes s;
+
+            function test() public {
+                bytes b = hex"4142";
+                bytes2 c = hex"4344";
+
+                assert(bytes.concat().length == 0);
+                assert(bytes.concat(b) == hex"4142");
+                assert(bytes.concat(b, c) == hex"41424344");
+                assert(bytes.concat(c, hex"45", b) == hex"4344454142");
+
+                s = bytes.concat(s, b);
+                s = bytes.concat(s, c);
+
+                assert(s == hex"41424344");
+            }
+        }"##,
+    );
+
+    runtime.function("test", Vec::new());
+
+    let ns = parse_and_resolve(
+        r#"
+        contract foo {
+            function test(uint8 a) public returns (bytes) {
+                return bytes.concat(a);
+            }
+        }"#,
+        Target::Substrate,
+    );
+
+    assert_eq!(
+        first_error(ns.diagnostics),
+        "‘bytes.concat’ expects bytes or fixed length bytes, not ‘uint8’"
+    );
+}
+
 #[test]
 fn string_abi_encode() {
     #[derive(Debug, PartialEq, Encode, Decode)]
@@ -3,6 +3,7 @@ use solang::file_cache::FileCache;
 use solang::sema::ast::Diagnostic;
 use solang::sema::ast::{Level, Namespace};
 use solang::{parse_and_resolve, Target};
+use std::collections::HashMap;
 
 fn parse_and_codegen(src: &'static str) -> Namespace {
     let mut cache = FileCache::new();
@@ -16,6 +17,14 @@ fn parse_and_codegen(src: &'static str) -> Namespace {
         vector_to_slice: false,
         opt_level: inkwell::OptimizationLevel::Default,
         math_overflow_check: false,
+        meter_gas: false,
+        strict_abi_decode: false,
+        max_return_data: None,
+        max_revert_data: None,
+        hash_host_byte_order: false,
+        contract_opt_level: HashMap::new(),
+        contract_size_limit: None,
+        contract_size_limit_error: false,
     };
 
     codegen(&mut ns, &opt);